@@ -0,0 +1,52 @@
+//! Renders TypeScript bindings from a command manifest.
+//!
+//! ```text
+//! tauri-commands-ts <manifest.json> <output.ts> [--check]
+//! ```
+
+use std::process::exit;
+
+use tauri_commands_codegen::Manifest;
+
+fn main() {
+    let mut check = false;
+    let mut paths = Vec::new();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => paths.push(arg),
+        }
+    }
+
+    let (manifest_path, output_path) = match paths.as_slice() {
+        [manifest_path, output_path] => (manifest_path, output_path),
+        _ => {
+            eprintln!("usage: tauri-commands-ts <manifest.json> <output.ts> [--check]");
+            exit(2);
+        }
+    };
+
+    let manifest = match Manifest::read(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("failed to read manifest {manifest_path}: {err}");
+            exit(1);
+        }
+    };
+
+    let result = if check {
+        manifest
+            .check_typescript(output_path)
+            .map_err(|err| err.to_string())
+    } else {
+        manifest
+            .write_typescript(output_path)
+            .map_err(|err| err.to_string())
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        exit(1);
+    }
+}
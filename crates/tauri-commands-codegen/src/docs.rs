@@ -0,0 +1,446 @@
+use std::{collections::HashSet, path::Path};
+
+use schemars::schema::Schema;
+use yasc::{
+    codegen::typescript::TypeScriptGenerator,
+    collection::Collection,
+    util::{type_name_of, CodeWriter},
+};
+
+use super::{
+    deprecated_prefix, invoke_name, unindent, write_file, Manifest, ManifestCommand,
+    VALID_REFERENCES,
+};
+
+#[derive(Clone, Copy)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+impl Format {
+    fn escape(self, s: &str) -> String {
+        match self {
+            Format::Markdown => s.replace('|', "\\|"),
+            Format::Html => s
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;"),
+        }
+    }
+
+    fn code(self, s: &str) -> String {
+        match self {
+            Format::Markdown => format!("`{}`", self.escape(s)),
+            Format::Html => format!("<code>{}</code>", self.escape(s)),
+        }
+    }
+
+    fn type_link(self, name: &str) -> String {
+        match self {
+            Format::Markdown => format!("[`{name}`](#{})", type_anchor(name)),
+            Format::Html => format!(
+                "<a href=\"#{}\"><code>{}</code></a>",
+                type_anchor(name),
+                self.escape(name)
+            ),
+        }
+    }
+}
+
+/// Renders the commands and types of a manifest as an API reference.
+struct Reference<'m> {
+    manifest: &'m Manifest,
+    collection: Collection,
+    gen: TypeScriptGenerator,
+    /// Names of the type definitions that can be linked to.
+    type_names: HashSet<String>,
+    format: Format,
+}
+
+impl<'m> Reference<'m> {
+    fn new(manifest: &'m Manifest, format: Format) -> Self {
+        let collection = Collection::default();
+        collection.add_definitions(&manifest.definitions);
+
+        let type_names = collection
+            .read()
+            .iter()
+            .filter_map(|(id, schema)| type_name_of(schema, Some(id)))
+            .collect();
+
+        Self {
+            manifest,
+            gen: TypeScriptGenerator::new(collection.clone()),
+            collection,
+            type_names,
+            format,
+        }
+    }
+
+    fn title(&self) -> &str {
+        self.manifest.plugin.as_deref().unwrap_or("Commands")
+    }
+
+    fn commands(&self) -> impl Iterator<Item = (&'m String, &'m ManifestCommand)> {
+        self.manifest.commands.iter().filter(|(_, cmd)| !cmd.hidden)
+    }
+
+    /// The TypeScript type of the schema on a single line, with the names
+    /// of definitions linked.
+    fn type_of(&self, schema: Option<&Schema>) -> String {
+        let ty = match schema {
+            Some(Schema::Object(s)) => {
+                let mut sw = CodeWriter::default();
+                self.gen
+                    .generate_name_or_type(s, &mut sw)
+                    .expect(VALID_REFERENCES);
+                sw.finish()
+            }
+            Some(Schema::Bool(false)) => "never".to_string(),
+            Some(Schema::Bool(true)) | None => "unknown".to_string(),
+        };
+
+        let mut out = String::new();
+        let mut code = String::new();
+        let mut in_string = false;
+
+        for token in tokens(&ty) {
+            if token.starts_with('"') {
+                in_string = !in_string;
+            }
+
+            if !in_string && self.type_names.contains(token) {
+                if !code.is_empty() {
+                    out += &self.format.code(&code);
+                    code.clear();
+                }
+                out += &self.format.type_link(token);
+            } else if !in_string {
+                // Types of inline objects span multiple lines, which would break tables.
+                for c in token.chars() {
+                    if !c.is_whitespace() {
+                        code.push(c);
+                    } else if !code.ends_with(' ') {
+                        code.push(' ');
+                    }
+                }
+            } else {
+                code += token;
+            }
+        }
+
+        if !code.is_empty() {
+            out += &self.format.code(&code);
+        }
+
+        out
+    }
+
+    /// The TypeScript definitions of all types, in a stable order.
+    fn definitions(&self) -> Vec<(String, String)> {
+        let schemas = self.collection.read();
+
+        self.collection
+            .sorted_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let name = type_name_of(schemas.get(&id)?, Some(&id))?;
+                let mut sw = CodeWriter::default();
+                self.gen
+                    .generate_definition(&id, None, &mut sw)
+                    .expect(VALID_REFERENCES);
+                Some((name, sw.finish()))
+            })
+            .collect()
+    }
+
+    fn markdown(&self) -> String {
+        let mut md = format!("# {}\n\n", self.title());
+
+        md += &format!("API hash: `{}`\n\n## Commands\n\n", self.manifest.api_hash);
+
+        for (name, cmd) in self.commands() {
+            md += &format!(
+                "### `{}`\n\n",
+                invoke_name(name, self.manifest.plugin.as_deref())
+            );
+
+            if let Some(note) = &cmd.deprecated {
+                md += "> **Deprecated**";
+                if !note.is_empty() {
+                    md += ": ";
+                    md += note;
+                }
+                md += "\n\n";
+            }
+
+            let docs = unindent(&cmd.docs);
+
+            if !docs.is_empty() {
+                md += &docs;
+                md += "\n\n";
+            }
+
+            if !cmd.features.is_empty() {
+                let features: Vec<_> = cmd.features.iter().map(|f| self.format.code(f)).collect();
+                md += &format!("Features: {}\n\n", features.join(", "));
+            }
+
+            if cmd.untyped {
+                md += "The arguments and output of this command are unknown.\n\n";
+                continue;
+            }
+
+            let args: Vec<_> = cmd.args.iter().filter(|arg| !arg.hidden).collect();
+
+            if !args.is_empty() {
+                md += "| Argument | Type | Description |\n| --- | --- | --- |\n";
+
+                for arg in args {
+                    let mut docs = arg.docs.replace('\n', " ");
+
+                    if let Some(note) = &arg.deprecated {
+                        docs = deprecated_prefix(note) + &docs;
+                    }
+
+                    let name = if arg.flatten {
+                        format!("...{}", arg.name)
+                    } else {
+                        arg.name.clone()
+                    };
+
+                    md += &format!(
+                        "| {} | {} | {} |\n",
+                        self.format.code(&name),
+                        self.type_of(Some(&arg.schema)),
+                        self.format.escape(docs.trim())
+                    );
+                }
+
+                md += "\n";
+            }
+
+            md += &format!("**Returns** {}", self.type_of(cmd.output.as_ref()));
+
+            if !cmd.returns.is_empty() {
+                md += ": ";
+                md += &cmd.returns;
+            }
+
+            md += "\n\n";
+        }
+
+        let definitions = self.definitions();
+
+        if !definitions.is_empty() {
+            md += "## Types\n\n";
+
+            for (name, definition) in definitions {
+                md += &format!(
+                    "### <a name=\"{}\"></a>`{name}`\n\n```ts\n{definition}\n```\n\n",
+                    type_anchor(&name)
+                );
+            }
+        }
+
+        md.truncate(md.trim_end().len());
+        md.push('\n');
+        md
+    }
+
+    fn html(&self) -> String {
+        let f = self.format;
+        let title = f.escape(self.title());
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body{{font-family:sans-serif;max-width:60em;margin:auto;padding:1em}}\
+             table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:.3em .6em;text-align:left}}\
+             pre{{background:#f6f6f6;padding:.6em;overflow:auto}}</style>\n</head>\n<body>\n"
+        );
+
+        html += &format!("<h1>{title}</h1>\n");
+        html += &format!(
+            "<p>API hash: {}</p>\n<h2>Commands</h2>\n",
+            f.code(&self.manifest.api_hash)
+        );
+
+        for (name, cmd) in self.commands() {
+            html += &format!(
+                "<section>\n<h3>{}</h3>\n",
+                f.code(&invoke_name(name, self.manifest.plugin.as_deref()))
+            );
+
+            if let Some(note) = &cmd.deprecated {
+                html += "<p><strong>Deprecated</strong>";
+                if !note.is_empty() {
+                    html += ": ";
+                    html += &f.escape(note);
+                }
+                html += "</p>\n";
+            }
+
+            for paragraph in unindent(&cmd.docs).split("\n\n") {
+                if !paragraph.trim().is_empty() {
+                    html += &format!("<p>{}</p>\n", f.escape(paragraph.trim()));
+                }
+            }
+
+            if !cmd.features.is_empty() {
+                let features: Vec<_> = cmd.features.iter().map(|feature| f.code(feature)).collect();
+                html += &format!("<p>Features: {}</p>\n", features.join(", "));
+            }
+
+            if cmd.untyped {
+                html +=
+                    "<p>The arguments and output of this command are unknown.</p>\n</section>\n";
+                continue;
+            }
+
+            let args: Vec<_> = cmd.args.iter().filter(|arg| !arg.hidden).collect();
+
+            if !args.is_empty() {
+                html += "<table>\n<tr><th>Argument</th><th>Type</th><th>Description</th></tr>\n";
+
+                for arg in args {
+                    let mut docs = arg.docs.clone();
+
+                    if let Some(note) = &arg.deprecated {
+                        docs = deprecated_prefix(note) + &docs;
+                    }
+
+                    let name = if arg.flatten {
+                        format!("...{}", arg.name)
+                    } else {
+                        arg.name.clone()
+                    };
+
+                    html += &format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        f.code(&name),
+                        self.type_of(Some(&arg.schema)),
+                        f.escape(docs.trim())
+                    );
+                }
+
+                html += "</table>\n";
+            }
+
+            html += &format!(
+                "<p><strong>Returns</strong> {}",
+                self.type_of(cmd.output.as_ref())
+            );
+
+            if !cmd.returns.is_empty() {
+                html += ": ";
+                html += &f.escape(&cmd.returns);
+            }
+
+            html += "</p>\n</section>\n";
+        }
+
+        let definitions = self.definitions();
+
+        if !definitions.is_empty() {
+            html += "<h2>Types</h2>\n";
+
+            for (name, definition) in definitions {
+                html += &format!(
+                    "<section id=\"{}\">\n<h3>{}</h3>\n<pre><code>{}</code></pre>\n</section>\n",
+                    type_anchor(&name),
+                    f.code(&name),
+                    f.escape(&definition)
+                );
+            }
+        }
+
+        html += "</body>\n</html>\n";
+        html
+    }
+}
+
+impl Manifest {
+    /// Generate a Markdown API reference of the commands and their types.
+    pub fn generate_markdown(&self) -> String {
+        Reference::new(self, Format::Markdown).markdown()
+    }
+
+    pub fn write_markdown(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        write_file(path.as_ref(), &self.generate_markdown())
+    }
+
+    /// Generate a static HTML page with the same contents as [`Manifest::generate_markdown`].
+    pub fn generate_html(&self) -> String {
+        Reference::new(self, Format::Html).html()
+    }
+
+    pub fn write_html(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        write_file(path.as_ref(), &self.generate_html())
+    }
+}
+
+fn type_anchor(name: &str) -> String {
+    format!("type-{name}")
+}
+
+/// Splits a type into identifiers and everything in between.
+fn tokens(ty: &str) -> impl Iterator<Item = &str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let mut rest = ty;
+
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = if is_ident(first) {
+            rest.find(|c| !is_ident(c))
+        } else if first == '"' {
+            Some(1)
+        } else {
+            rest.find(|c| is_ident(c) || c == '"')
+        }
+        .unwrap_or(rest.len());
+
+        let (token, tail) = rest.split_at(end);
+        rest = tail;
+        Some(token)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_line_types_stay_in_table_cells() {
+        let manifest = Manifest::from_json(
+            &serde_json::json!({
+                "version": 1,
+                "apiHash": "0",
+                "definitions": {},
+                "commands": {
+                    "count": {
+                        "args": [{
+                            "name": "counts",
+                            "key": "counts",
+                            "schema": {
+                                "type": "object",
+                                "additionalProperties": { "type": "number" },
+                            },
+                        }],
+                    },
+                },
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let md = manifest.generate_markdown();
+        let row = md
+            .lines()
+            .find(|line| line.starts_with("| `counts`"))
+            .unwrap();
+
+        assert_eq!(row, "| `counts` | `{ [key: string]: number; }` |  |");
+    }
+}
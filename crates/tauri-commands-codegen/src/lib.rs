@@ -0,0 +1,869 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use heck::{ToLowerCamelCase, ToSnakeCase, ToUpperCamelCase};
+use schemars::schema::Schema;
+use yasc::{codegen::typescript::TypeScriptGenerator, collection::Collection, util::CodeWriter};
+
+pub use yasc::codegen::typescript::TypeScriptGeneratorOptions;
+
+mod docs;
+mod manifest;
+mod openrpc;
+
+pub use manifest::{Manifest, ManifestArg, ManifestCommand, MANIFEST_VERSION};
+pub use openrpc::OPENRPC_VERSION;
+
+/// The built-in command that returns the API hash of the running backend,
+/// it must match `tauri_commands::API_HASH_COMMAND`.
+pub const API_HASH_COMMAND: &str = "__tauri_commands_api_hash";
+
+impl ManifestCommand {
+    fn generate_ts_handler(
+        &self,
+        cmd_name: &str,
+        fn_name: &str,
+        plugin_name: Option<&str>,
+        gen: &TypeScriptGenerator,
+        options: &TypeScriptOptions,
+        sw: &mut CodeWriter,
+    ) {
+        let invoke_name = invoke_name(cmd_name, plugin_name);
+        let invoke_fn = &options.invoke_fn;
+
+        let mut tags = String::new();
+
+        // Argument names are only visible inside the function, so they are always renamed.
+        let mut idents = Identifiers::with_imports(options);
+        let args: Vec<_> = self
+            .args
+            .iter()
+            .filter(|arg| !arg.hidden)
+            .map(|arg| (arg, idents.insert(&arg.name, "")))
+            .collect();
+
+        for (arg, arg_name) in &args {
+            let description = match &arg.deprecated {
+                Some(note) => deprecated_prefix(note) + &arg.docs,
+                None => arg.docs.clone(),
+            };
+
+            if !description.is_empty() {
+                tags += &format!("@param {} {}\n", arg_name, description.trim_end());
+            }
+        }
+
+        if !self.returns.is_empty() {
+            tags += &format!("@returns {}\n", self.returns);
+        }
+
+        if options.feature_tags {
+            for feature in &self.features {
+                tags += &format!("@feature {feature}\n");
+            }
+        }
+
+        if let Some(note) = &self.deprecated {
+            tags += "@deprecated ";
+            tags += note;
+        }
+
+        let mut docs = unindent(&self.docs);
+
+        if !docs.is_empty() && !tags.is_empty() {
+            docs.push('\n');
+        }
+
+        docs += &tags;
+
+        sw.doc_comment(&docs);
+        sw.push_str("export function ");
+        sw.push_str(fn_name);
+
+        if self.untyped {
+            sw.line("(args: Record<string, unknown> = {}): Promise<unknown> {");
+            sw.indent();
+            sw.line(&format!("return {invoke_fn}('{invoke_name}', args);"));
+            sw.dedent();
+            sw.line("}");
+            return;
+        }
+
+        sw.push_str("(");
+
+        let mut fields = Vec::new();
+
+        let mut params = 0;
+
+        for (arg, arg_name) in &args {
+            if let Schema::Object(s) = &arg.schema {
+                if params > 0 {
+                    sw.push_str(", ");
+                }
+                params += 1;
+                sw.push_str(arg_name);
+                sw.push_str(": ");
+                gen.generate_name_or_type(s, sw).expect(VALID_REFERENCES);
+            }
+
+            if arg.flatten {
+                fields.push(format!("...{arg_name}"));
+            } else if arg.key == *arg_name {
+                fields.push(arg_name.clone());
+            } else if is_identifier(&arg.key) {
+                fields.push(format!("{}: {arg_name}", arg.key));
+            } else {
+                fields.push(format!(
+                    "{}: {arg_name}",
+                    serde_json::to_string(&arg.key).unwrap()
+                ));
+            }
+        }
+
+        let msg_obj = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        };
+
+        sw.push_str("): Promise<");
+        match &self.output {
+            Some(Schema::Bool(true)) | None => sw.push_str("unknown"),
+            Some(Schema::Bool(false)) => sw.push_str("never"),
+            Some(Schema::Object(s)) => gen.generate_name_or_type(s, sw).expect(VALID_REFERENCES),
+        }
+        sw.line("> {");
+        sw.indent();
+        sw.line(&format!("return {invoke_fn}('{invoke_name}', {msg_obj});"));
+        sw.dedent();
+        sw.line("}");
+    }
+}
+
+/// Options for the generated TypeScript bindings.
+#[derive(Debug, Clone)]
+pub struct TypeScriptOptions {
+    /// The module the invoke function is imported from, nothing is imported
+    /// if it is `None`, e.g. for `window.__TAURI__.invoke`.
+    pub invoke_import: Option<Cow<'static, str>>,
+    /// The invoke function, it is called with the command name and the arguments.
+    ///
+    /// It has to be a plain identifier if it is imported.
+    pub invoke_fn: Cow<'static, str>,
+    /// How command names are turned into function names.
+    pub naming: FunctionNaming,
+    /// What happens if a function name is already used, or is not a valid identifier.
+    pub on_conflict: NameConflicts,
+    /// Written at the top of the file as line comments.
+    pub header: Option<Cow<'static, str>>,
+    /// Comments that disable linters, e.g. `/* eslint-disable */`, written before the imports.
+    pub lint_disable: Vec<Cow<'static, str>>,
+    /// Tag the functions with the Cargo features their commands are gated behind.
+    pub feature_tags: bool,
+    /// Leave out commands that are disabled by their cfg predicates, instead of
+    /// generating untyped functions for them.
+    pub omit_disabled: bool,
+    /// Options for the generated type definitions.
+    pub generator: TypeScriptGeneratorOptions,
+}
+
+impl Default for TypeScriptOptions {
+    fn default() -> Self {
+        Self {
+            invoke_import: Some("@tauri-apps/api".into()),
+            invoke_fn: "invoke".into(),
+            naming: FunctionNaming::default(),
+            on_conflict: NameConflicts::default(),
+            header: None,
+            lint_disable: Vec::new(),
+            feature_tags: true,
+            omit_disabled: false,
+            generator: TypeScriptGeneratorOptions::default(),
+        }
+    }
+}
+
+/// The naming of the generated functions.
+#[derive(Debug, Clone, Copy)]
+pub enum FunctionNaming {
+    /// `add numbers` becomes `addNumbers`.
+    LowerCamelCase,
+    /// `add numbers` becomes `add_numbers`.
+    SnakeCase,
+    /// `add numbers` becomes `AddNumbers`.
+    UpperCamelCase,
+    Custom(fn(&str) -> String),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for FunctionNaming {
+    fn default() -> Self {
+        FunctionNaming::LowerCamelCase
+    }
+}
+
+impl FunctionNaming {
+    pub fn apply(&self, cmd_name: &str) -> String {
+        match self {
+            FunctionNaming::LowerCamelCase => cmd_name.to_lower_camel_case(),
+            FunctionNaming::SnakeCase => cmd_name.to_snake_case(),
+            FunctionNaming::UpperCamelCase => cmd_name.to_upper_camel_case(),
+            FunctionNaming::Custom(f) => f(cmd_name),
+        }
+    }
+}
+
+/// Handling of function names that collide or are reserved words.
+#[derive(Debug, Clone, Copy)]
+pub enum NameConflicts {
+    /// Reserved words get a `_` suffix, and names that are already used
+    /// a number suffix, commands are processed in alphabetical order.
+    Rename,
+    /// Fail with a [`NameConflictError`] that lists all conflicts.
+    Error,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for NameConflicts {
+    fn default() -> Self {
+        NameConflicts::Rename
+    }
+}
+
+/// Function names that are already used or are not valid identifiers,
+/// returned if [`NameConflicts::Error`] is used.
+#[derive(Debug, Clone)]
+pub struct NameConflictError {
+    pub conflicts: Vec<NameConflict>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NameConflict {
+    /// The command the function is generated for.
+    pub command: String,
+    /// The function name of the command.
+    pub function: String,
+    /// Why the name cannot be used, e.g. ``is already used by command `foo` ``.
+    pub reason: String,
+}
+
+impl std::fmt::Display for NameConflictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid function names:")?;
+
+        for conflict in &self.conflicts {
+            write!(
+                f,
+                "\ncommand `{}`: `{}` {}",
+                conflict.command, conflict.function, conflict.reason
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for NameConflictError {}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
+
+/// Error returned when checking generated bindings.
+#[derive(Debug)]
+pub enum CheckError {
+    Io(std::io::Error),
+    /// The bindings could not be generated with the given options.
+    Conflicts(NameConflictError),
+    /// The file differs from the generated bindings.
+    Stale {
+        path: PathBuf,
+        diff: String,
+    },
+}
+
+impl std::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckError::Io(err) => write!(f, "failed to read bindings: {err}"),
+            CheckError::Conflicts(err) => write!(f, "{err}"),
+            CheckError::Stale { path, diff } => write!(
+                f,
+                "bindings in {} are out of date, regenerate them (- on disk, + generated):\n{diff}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckError::Io(err) => Some(err),
+            CheckError::Conflicts(err) => Some(err),
+            CheckError::Stale { .. } => None,
+        }
+    }
+}
+
+fn check_file(path: &Path, contents: &str) -> Result<(), CheckError> {
+    let on_disk = std::fs::read_to_string(path).map_err(CheckError::Io)?;
+
+    if on_disk == contents {
+        return Ok(());
+    }
+
+    Err(CheckError::Stale {
+        path: path.to_path_buf(),
+        diff: line_diff(&on_disk, contents),
+    })
+}
+
+/// Diffs larger than this many lines on both sides multiplied
+/// only show the first difference, to bound the memory used.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// A minimal line diff based on the longest common subsequence,
+/// only changed lines are shown along with their line numbers.
+fn line_diff(old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+
+    // Only the lines between the common prefix and suffix have to be compared.
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    if old.is_empty() && new.is_empty() {
+        // Only line endings differ.
+        return "line endings differ\n".into();
+    }
+
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_DIFF_CELLS {
+        let mut diff = String::new();
+
+        if let Some(line) = old.first() {
+            diff += &format!("{:>5} - {}\n", prefix + 1, line);
+        }
+        if let Some(line) = new.first() {
+            diff += &format!("{:>5} + {}\n", prefix + 1, line);
+        }
+
+        diff += "  ... (more lines differ)\n";
+        return diff;
+    }
+
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..].
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff += &format!("{:>5} - {}\n", prefix + i + 1, old[i]);
+            i += 1;
+        } else {
+            diff += &format!("{:>5} + {}\n", prefix + j + 1, new[j]);
+            j += 1;
+        }
+    }
+
+    diff
+}
+
+impl Manifest {
+    /// Generate the same bindings as the commands the manifest was created from.
+    pub fn generate_typescript(&self) -> String {
+        self.generate_typescript_with(&TypeScriptOptions::default())
+            .expect("name conflicts are renamed by default")
+    }
+
+    pub fn generate_typescript_with(
+        &self,
+        options: &TypeScriptOptions,
+    ) -> Result<String, NameConflictError> {
+        let mut sw = CodeWriter::default();
+
+        if let Some(header) = &options.header {
+            for line in header.lines() {
+                sw.line(format!("// {line}").trim_end());
+            }
+        }
+
+        for comment in &options.lint_disable {
+            sw.line(comment);
+        }
+
+        let invoke_fn = &options.invoke_fn;
+
+        if let Some(invoke_import) = &options.invoke_import {
+            sw.line(&format!(
+                "import {{ {invoke_fn} }} from {};",
+                serde_json::to_string(invoke_import).unwrap()
+            ));
+            sw.line("");
+        }
+
+        sw.line(&format!("export const API_HASH = \"{}\";", self.api_hash));
+        sw.line("");
+
+        let c = Collection::default();
+
+        c.add_definitions(&self.definitions);
+
+        let gen = TypeScriptGenerator::new_with_options(c.clone(), options.generator.clone());
+
+        generate_definitions(&c, &gen, &mut sw);
+
+        let plugin_name = self.plugin.as_deref();
+        let fn_names = self.function_names(options)?;
+
+        for (name, cmd) in self.generated_commands(options) {
+            cmd.generate_ts_handler(
+                name,
+                &fn_names[name.as_str()],
+                plugin_name,
+                &gen,
+                options,
+                &mut sw,
+            );
+            sw.line("");
+        }
+
+        let check_name = invoke_name(API_HASH_COMMAND, plugin_name);
+
+        sw.doc_comment(
+            "Whether the running backend has the same commands as these bindings,\n\
+             also `false` if asking the backend fails.",
+        );
+        sw.line("export async function checkApiHash(): Promise<boolean> {");
+        sw.indent();
+        sw.line("try {");
+        sw.indent();
+        sw.line(&format!(
+            "return (await {invoke_fn}('{check_name}')) === API_HASH;"
+        ));
+        sw.dedent();
+        sw.line("} catch {");
+        sw.indent();
+        sw.line("return false;");
+        sw.dedent();
+        sw.line("}");
+        sw.dedent();
+        sw.line("}");
+
+        Ok(sw.finish())
+    }
+
+    pub fn write_typescript(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        write_file(path.as_ref(), &self.generate_typescript())
+    }
+
+    /// Name conflicts are returned as an error of the `InvalidInput` kind.
+    pub fn write_typescript_with(
+        &self,
+        path: impl AsRef<Path>,
+        options: &TypeScriptOptions,
+    ) -> Result<(), std::io::Error> {
+        let ts = self
+            .generate_typescript_with(options)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+        write_file(path.as_ref(), &ts)
+    }
+
+    /// Compare the bindings with the file at `path` instead of writing it.
+    pub fn check_typescript(&self, path: impl AsRef<Path>) -> Result<(), CheckError> {
+        check_file(path.as_ref(), &self.generate_typescript())
+    }
+
+    /// Panic with a diff if the bindings at `path` are stale, meant to be used in tests.
+    pub fn assert_typescript(&self, path: impl AsRef<Path>) {
+        if let Err(err) = self.check_typescript(path) {
+            panic!("{err}");
+        }
+    }
+
+    pub fn check_typescript_with(
+        &self,
+        path: impl AsRef<Path>,
+        options: &TypeScriptOptions,
+    ) -> Result<(), CheckError> {
+        let ts = self
+            .generate_typescript_with(options)
+            .map_err(CheckError::Conflicts)?;
+
+        check_file(path.as_ref(), &ts)
+    }
+
+    /// The commands functions are generated for.
+    fn generated_commands(
+        &self,
+        options: &TypeScriptOptions,
+    ) -> impl Iterator<Item = (&String, &ManifestCommand)> {
+        let omit_disabled = options.omit_disabled;
+
+        self.commands
+            .iter()
+            .filter(move |(_, cmd)| !(cmd.hidden || (omit_disabled && cmd.disabled)))
+    }
+
+    /// The function names of the generated commands, unique and valid identifiers.
+    fn function_names(
+        &self,
+        options: &TypeScriptOptions,
+    ) -> Result<BTreeMap<&str, String>, NameConflictError> {
+        let mut idents = Identifiers::with_imports(options);
+        idents.insert("API_HASH", "the API hash");
+        idents.insert("checkApiHash", "the API hash check");
+
+        let mut conflicts = Vec::new();
+
+        let fn_names = self
+            .generated_commands(options)
+            .map(|(name, _)| {
+                let fn_name = options.naming.apply(name);
+
+                if let Some(reason) = idents.conflict(&fn_name) {
+                    conflicts.push(NameConflict {
+                        command: name.clone(),
+                        function: fn_name.clone(),
+                        reason,
+                    });
+                }
+
+                let ident = idents.insert(&fn_name, &format!("command `{name}`"));
+                (name.as_str(), ident)
+            })
+            .collect();
+
+        match options.on_conflict {
+            NameConflicts::Error if !conflicts.is_empty() => Err(NameConflictError { conflicts }),
+            _ => Ok(fn_names),
+        }
+    }
+}
+
+/// Manifests are validated when they are parsed, so that rendering them
+/// cannot fail on references to missing definitions.
+const VALID_REFERENCES: &str = "references to missing definitions are rejected";
+
+fn generate_definitions(c: &Collection, gen: &TypeScriptGenerator, sw: &mut CodeWriter) {
+    for s in c.sorted_ids() {
+        gen.generate_definition(&s, None, sw)
+            .expect(VALID_REFERENCES);
+        sw.line("\n");
+    }
+}
+
+/// Removes the space doc comments start with from every line.
+fn unindent(docs: &str) -> String {
+    docs.lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Prepended to the description of deprecated arguments.
+fn deprecated_prefix(note: &str) -> String {
+    if note.is_empty() {
+        "Deprecated. ".into()
+    } else {
+        format!("Deprecated, {note}. ")
+    }
+}
+
+/// The name the command is invoked with from the frontend.
+fn invoke_name<'a>(cmd_name: &'a str, plugin_name: Option<&str>) -> Cow<'a, str> {
+    match plugin_name {
+        Some(plugin_name) => Cow::Owned(format!("plugin:{plugin_name}|{cmd_name}")),
+        None => Cow::Borrowed(cmd_name),
+    }
+}
+
+/// Reserved words of JavaScript and TypeScript that cannot be used as names.
+const RESERVED_WORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Identifiers used in a scope, along with what they are used by.
+#[derive(Default)]
+struct Identifiers {
+    used: HashMap<String, String>,
+}
+
+impl Identifiers {
+    /// Identifiers with the names imported by the bindings already used.
+    fn with_imports(options: &TypeScriptOptions) -> Self {
+        let mut idents = Self::default();
+
+        // Only the first part of e.g. `window.__TAURI__.invoke` is in scope.
+        let invoke_root = options.invoke_fn.split('.').next().unwrap_or_default();
+        idents.insert(invoke_root, "the invoke function");
+
+        idents
+    }
+
+    /// Why the name cannot be used as it is.
+    fn conflict(&self, name: &str) -> Option<String> {
+        if !is_identifier(name) {
+            Some("is not a valid identifier".into())
+        } else if RESERVED_WORDS.contains(&name) {
+            Some("is a reserved word".into())
+        } else {
+            self.used
+                .get(name)
+                .map(|owner| format!("is already used by {owner}"))
+        }
+    }
+
+    /// Uses the name, or a valid and unused identifier derived from it.
+    fn insert(&mut self, name: &str, owner: &str) -> String {
+        let mut ident: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        if !matches!(ident.chars().next(), Some(c) if !c.is_ascii_digit()) {
+            ident.insert(0, '_');
+        }
+
+        if RESERVED_WORDS.contains(&ident.as_str()) {
+            ident.push('_');
+        }
+
+        let mut unique = ident.clone();
+
+        for n in 2.. {
+            if !self.used.contains_key(&unique) {
+                break;
+            }
+            unique = format!("{ident}{n}");
+        }
+
+        self.used.insert(unique.clone(), owner.to_string());
+        unique
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_changed_lines() {
+        assert_eq!(
+            line_diff("a\nb\nc\nd\n", "a\nx\nc\nd\ne\n"),
+            "    2 - b\n    2 + x\n    5 + e\n"
+        );
+        assert_eq!(line_diff("a\nb\n", "b\n"), "    1 - a\n");
+    }
+
+    #[test]
+    fn line_diff_line_endings() {
+        assert_eq!(line_diff("a\r\nb\r\n", "a\nb\n"), "line endings differ\n");
+    }
+
+    #[test]
+    fn line_diff_falls_back_for_large_inputs() {
+        let old: String = (0..2000).map(|i| format!("old {i}\n")).collect();
+        let new: String = (0..2000).map(|i| format!("new {i}\n")).collect();
+
+        assert_eq!(
+            line_diff(&format!("same\n{old}"), &format!("same\n{new}")),
+            "    2 - old 0\n    2 + new 0\n  ... (more lines differ)\n"
+        );
+    }
+
+    #[test]
+    fn check_file_reports_stale_bindings() {
+        let path =
+            std::env::temp_dir().join(format!("tauri-commands-check-{}.ts", std::process::id()));
+        std::fs::write(&path, "a\nb\n").unwrap();
+
+        assert!(check_file(&path, "a\nb\n").is_ok());
+
+        match check_file(&path, "a\nc\n") {
+            Err(CheckError::Stale { diff, .. }) => assert_eq!(diff, "    2 - b\n    2 + c\n"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(check_file(&path, ""), Err(CheckError::Io(_))));
+    }
+
+    #[test]
+    fn identifiers_are_unique_and_valid() {
+        let mut idents = Identifiers::with_imports(&TypeScriptOptions {
+            invoke_fn: "window.__TAURI__.invoke".into(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            idents.conflict("window").unwrap(),
+            "is already used by the invoke function"
+        );
+        assert_eq!(idents.insert("window", "arg"), "window2");
+        assert_eq!(idents.insert("window", "arg"), "window3");
+
+        assert_eq!(idents.conflict("delete").unwrap(), "is a reserved word");
+        assert_eq!(idents.insert("delete", "arg"), "delete_");
+
+        assert_eq!(idents.conflict("2fa").unwrap(), "is not a valid identifier");
+        assert_eq!(idents.insert("2fa", "arg"), "_2fa");
+        assert_eq!(idents.insert("add-numbers", "arg"), "add_numbers");
+
+        assert_eq!(idents.conflict("valid"), None);
+        assert_eq!(idents.insert("valid", "arg"), "valid");
+    }
+
+    fn manifest(commands: &[&str]) -> Manifest {
+        let commands: serde_json::Map<_, _> = commands
+            .iter()
+            .map(|name| (name.to_string(), serde_json::json!({ "args": [] })))
+            .collect();
+
+        Manifest::from_json(
+            &serde_json::json!({
+                "version": MANIFEST_VERSION,
+                "apiHash": "0",
+                "definitions": {},
+                "commands": commands,
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn function_names_are_renamed() {
+        let manifest = manifest(&["add numbers", "add_numbers", "check api hash", "delete"]);
+        let names = manifest
+            .function_names(&TypeScriptOptions::default())
+            .unwrap();
+
+        assert_eq!(names["add numbers"], "addNumbers");
+        assert_eq!(names["add_numbers"], "addNumbers2");
+        assert_eq!(names["check api hash"], "checkApiHash2");
+        assert_eq!(names["delete"], "delete_");
+    }
+
+    #[test]
+    fn function_name_conflicts_are_errors() {
+        let manifest = manifest(&["add numbers", "add_numbers", "delete", "hello"]);
+        let err = manifest
+            .function_names(&TypeScriptOptions {
+                on_conflict: NameConflicts::Error,
+                ..Default::default()
+            })
+            .unwrap_err();
+
+        let conflicts: Vec<_> = err
+            .conflicts
+            .iter()
+            .map(|c| (c.command.as_str(), c.function.as_str(), c.reason.as_str()))
+            .collect();
+
+        assert_eq!(
+            conflicts,
+            [
+                (
+                    "add_numbers",
+                    "addNumbers",
+                    "is already used by command `add numbers`"
+                ),
+                ("delete", "delete", "is a reserved word"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::BTreeMap, path::Path};
+
+use schemars::{
+    schema::{Schema, SchemaObject},
+    visit::Visitor,
+    Map,
+};
+use serde::{Deserialize, Serialize};
+
+/// The version of the manifest format, it is increased on breaking changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// All metadata of the commands, serialized as JSON it can be used
+/// by other tools without compiling the backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub version: u32,
+    /// The name of the plugin if the commands are invoked through one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    pub api_hash: String,
+    /// The schema definitions referenced by the commands.
+    pub definitions: Map<String, Schema>,
+    pub commands: BTreeMap<String, ManifestCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestCommand {
+    #[serde(default)]
+    pub docs: String,
+    /// Description of the output.
+    #[serde(default)]
+    pub returns: String,
+    #[serde(default)]
+    pub untyped: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// The command is disabled in the build the manifest was created from.
+    #[serde(default)]
+    pub disabled: bool,
+    /// The deprecation note, empty if the command is deprecated without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    pub args: Vec<ManifestArg>,
+    /// The schema of the output, missing if it is unknown.
+    #[serde(default)]
+    pub output: Option<Schema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestArg {
+    pub name: String,
+    /// The key of the argument in the payload.
+    pub key: String,
+    #[serde(default)]
+    pub docs: String,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub flatten: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    pub schema: Schema,
+}
+
+impl Manifest {
+    /// Parse a manifest, manifests of newer versions and ones with
+    /// references to missing definitions are rejected.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let manifest: Self = serde_json::from_str(json)?;
+
+        if manifest.version > MANIFEST_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported manifest version {}, expected at most {MANIFEST_VERSION}",
+                manifest.version
+            )));
+        }
+
+        manifest.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(manifest)
+    }
+
+    /// Checks that the bindings can be generated from the schemas.
+    fn validate(&self) -> Result<(), String> {
+        for name in self.definitions.keys() {
+            if format!("root://{name}").parse::<url::Url>().is_err() {
+                return Err(format!("invalid definition name `{name}`"));
+            }
+        }
+
+        let mut refs = CheckReferences {
+            definitions: &self.definitions,
+            invalid: None,
+        };
+
+        let schemas = self.definitions.values().chain(
+            self.commands
+                .values()
+                .flat_map(|cmd| cmd.args.iter().map(|arg| &arg.schema).chain(&cmd.output)),
+        );
+
+        for schema in schemas {
+            refs.visit_schema(&mut schema.clone());
+
+            if let Some(reference) = refs.invalid {
+                return Err(format!("reference to missing definition `{reference}`"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(Into::into)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        crate::write_file(path.as_ref(), &self.to_json())
+    }
+}
+
+/// Finds references that are not to one of the definitions.
+struct CheckReferences<'a> {
+    definitions: &'a Map<String, Schema>,
+    invalid: Option<String>,
+}
+
+impl Visitor for CheckReferences<'_> {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        match &schema.reference {
+            Some(reference) => {
+                let found = matches!(
+                    reference.strip_prefix("#/definitions/"),
+                    Some(name) if self.definitions.contains_key(name)
+                );
+
+                if !found && self.invalid.is_none() {
+                    self.invalid = Some(reference.clone());
+                }
+            }
+            None => schemars::visit::visit_schema_object(self, schema),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_with_arg(schema: serde_json::Value) -> String {
+        serde_json::json!({
+            "version": MANIFEST_VERSION,
+            "apiHash": "0",
+            "definitions": { "Known": { "type": "string" } },
+            "commands": {
+                "hello": {
+                    "args": [{ "name": "request", "key": "request", "schema": schema }],
+                },
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn missing_definitions_are_rejected() {
+        let json = manifest_with_arg(serde_json::json!({ "$ref": "#/definitions/Known" }));
+        assert!(Manifest::from_json(&json).is_ok());
+
+        let json = manifest_with_arg(serde_json::json!({
+            "type": "array",
+            "items": { "$ref": "#/definitions/Missing" },
+        }));
+        let err = Manifest::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("#/definitions/Missing"), "{err}");
+    }
+}
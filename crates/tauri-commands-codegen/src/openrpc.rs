@@ -0,0 +1,254 @@
+use std::path::Path;
+
+use schemars::schema::{InstanceType, Schema, SingleOrVec};
+use serde_json::{json, Value};
+
+use super::{invoke_name, unindent, write_file, Manifest, ManifestArg, ManifestCommand};
+
+/// The OpenRPC version of the generated documents.
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+impl Manifest {
+    /// Generate an OpenRPC document describing the commands as methods.
+    ///
+    /// The title is the plugin name if there is one, and the version is the API hash.
+    pub fn generate_openrpc(&self) -> String {
+        let methods: Vec<_> = self
+            .commands
+            .iter()
+            .filter(|(_, cmd)| !cmd.hidden)
+            .map(|(name, cmd)| self.openrpc_method(name, cmd))
+            .collect();
+
+        let schemas: serde_json::Map<_, _> = self
+            .definitions
+            .iter()
+            .map(|(name, schema)| (name.clone(), schema_value(schema)))
+            .collect();
+
+        let doc = json!({
+            "openrpc": OPENRPC_VERSION,
+            "info": {
+                "title": self.plugin.as_deref().unwrap_or("Tauri commands"),
+                "version": self.api_hash,
+            },
+            "methods": methods,
+            "components": {
+                "schemas": schemas,
+            },
+        });
+
+        serde_json::to_string_pretty(&doc).unwrap()
+    }
+
+    pub fn write_openrpc(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        write_file(path.as_ref(), &self.generate_openrpc())
+    }
+
+    fn openrpc_method(&self, name: &str, cmd: &ManifestCommand) -> Value {
+        let mut method = serde_json::Map::new();
+
+        method.insert(
+            "name".into(),
+            invoke_name(name, self.plugin.as_deref()).into(),
+        );
+
+        let mut description = unindent(&cmd.docs);
+
+        if let Some(note) = cmd.deprecated.as_deref().filter(|note| !note.is_empty()) {
+            if !description.is_empty() {
+                description += "\n\n";
+            }
+            description += "Deprecated, ";
+            description += note;
+            description += ".";
+        }
+
+        if !description.is_empty() {
+            method.insert("description".into(), description.into());
+        }
+
+        if !cmd.features.is_empty() {
+            let tags: Vec<_> = cmd
+                .features
+                .iter()
+                .map(|feature| json!({ "name": feature }))
+                .collect();
+            method.insert("tags".into(), tags.into());
+        }
+
+        // The params of untyped commands are unknown, so they are left out
+        // instead of claiming that there are none.
+        if !cmd.untyped {
+            let mut params = Vec::new();
+
+            for arg in cmd.args.iter().filter(|arg| !arg.hidden) {
+                if arg.flatten {
+                    params.extend(self.flattened_params(arg));
+                } else {
+                    params.push(arg_param(arg));
+                }
+            }
+
+            method.insert("params".into(), params.into());
+            method.insert("paramStructure".into(), "by-name".into());
+        }
+
+        let mut result = json!({
+            "name": "result",
+            "schema": cmd.output.as_ref().map_or(json!({}), schema_value),
+        });
+
+        if !cmd.returns.is_empty() {
+            result["description"] = cmd.returns.clone().into();
+        }
+
+        method.insert("result".into(), result);
+
+        if cmd.deprecated.is_some() {
+            method.insert("deprecated".into(), true.into());
+        }
+
+        method.into()
+    }
+
+    /// The fields of a flattened argument are separate params, if they can be resolved.
+    fn flattened_params(&self, arg: &ManifestArg) -> Vec<Value> {
+        let object = match &arg.schema {
+            Schema::Object(s) => match &s.reference {
+                Some(reference) => reference
+                    .strip_prefix("#/definitions/")
+                    .and_then(|name| self.definitions.get(name)),
+                None => Some(&arg.schema),
+            },
+            Schema::Bool(_) => None,
+        }
+        .and_then(|s| match s {
+            Schema::Object(s) => s.object.as_ref(),
+            Schema::Bool(_) => None,
+        });
+
+        let object = match object {
+            Some(object) => object,
+            None => return vec![arg_param(arg)],
+        };
+
+        object
+            .properties
+            .iter()
+            .map(|(name, schema)| {
+                let docs = match schema {
+                    Schema::Object(s) => s
+                        .metadata
+                        .as_ref()
+                        .and_then(|m| m.description.as_deref())
+                        .unwrap_or_default(),
+                    Schema::Bool(_) => "",
+                };
+
+                let mut param = param(name, docs, schema, arg.deprecated.is_some());
+                param["required"] = object.required.contains(name).into();
+                param
+            })
+            .collect()
+    }
+}
+
+fn arg_param(arg: &ManifestArg) -> Value {
+    param(&arg.key, &arg.docs, &arg.schema, arg.deprecated.is_some())
+}
+
+fn param(name: &str, docs: &str, schema: &Schema, deprecated: bool) -> Value {
+    let mut param = json!({
+        "name": name,
+        "schema": schema_value(schema),
+        "required": !accepts_null(schema),
+    });
+
+    if !docs.is_empty() {
+        param["description"] = docs.into();
+    }
+
+    if deprecated {
+        param["deprecated"] = true.into();
+    }
+
+    param
+}
+
+/// Missing arguments are deserialized from `null`, so they are only required if it is not allowed.
+fn accepts_null(schema: &Schema) -> bool {
+    let s = match schema {
+        Schema::Bool(b) => return *b,
+        Schema::Object(s) => s,
+    };
+
+    let null_type = match &s.instance_type {
+        Some(SingleOrVec::Single(ty)) => **ty == InstanceType::Null,
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Null),
+        None => false,
+    };
+
+    null_type
+        || s.subschemas
+            .as_ref()
+            .and_then(|sub| sub.any_of.as_ref())
+            .into_iter()
+            .flatten()
+            .any(accepts_null)
+}
+
+/// Serializes the schema with references pointing to the OpenRPC components.
+fn schema_value(schema: &Schema) -> Value {
+    let mut value = serde_json::to_value(schema).unwrap();
+    rewrite_refs(&mut value);
+    value
+}
+
+fn rewrite_refs(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(s) if key == "$ref" => {
+                        if let Some(name) = s.strip_prefix("#/definitions/") {
+                            *s = format!("#/components/schemas/{name}");
+                        }
+                    }
+                    _ => rewrite_refs(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(rewrite_refs),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openrpc_methods() {
+        let manifest = Manifest::from_json(
+            &serde_json::json!({
+                "version": 1,
+                "apiHash": "0",
+                "definitions": {},
+                "commands": {
+                    "delete": { "docs": " Deletes.\n\n # Foo\n bar", "args": [] },
+                    "legacy": { "untyped": true, "args": [] },
+                },
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let doc: Value = serde_json::from_str(&manifest.generate_openrpc()).unwrap();
+        let methods = doc["methods"].as_array().unwrap();
+
+        assert_eq!(methods[0]["description"], "Deletes.\n\n# Foo\nbar");
+        assert_eq!(methods[0]["params"], json!([]));
+        assert!(methods[1].get("params").is_none());
+    }
+}
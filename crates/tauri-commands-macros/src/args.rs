@@ -0,0 +1,112 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{visit_mut::VisitMut, GenericParam, Ident, PatType, Signature, TypePath};
+
+use crate::{command::typed_inputs, options::ArgOptions};
+
+/// A typed argument of a command along with its `#[arg(...)]` options.
+pub(crate) struct CommandArg<'a> {
+    pub(crate) arg: &'a PatType,
+    pub(crate) options: ArgOptions,
+}
+
+pub(crate) fn command_args(sig: &Signature) -> syn::Result<Vec<CommandArg<'_>>> {
+    typed_inputs(sig)
+        .into_iter()
+        .map(|arg| {
+            Ok(CommandArg {
+                arg,
+                options: ArgOptions::from_attrs(&arg.attrs)?,
+            })
+        })
+        .collect()
+}
+
+/// The type parameters of a command, which can only be runtimes.
+pub(crate) fn runtime_params(sig: &Signature) -> Vec<Ident> {
+    sig.generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(ty) => Some(ty.ident.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the function can be registered as is, without a wrapping closure.
+pub(crate) fn is_plain(args: &[CommandArg]) -> bool {
+    args.iter()
+        .all(|arg| !arg.options.skip && !arg.options.flatten)
+}
+
+/// The parameters of a closure that extracts the arguments of a command, and the
+/// expressions that pass them on to the original function.
+///
+/// Runtime type parameters of the function are replaced with `runtime`.
+pub(crate) fn handler_args(
+    args: &[CommandArg],
+    runtime_params: &[Ident],
+    runtime: &Ident,
+) -> (Vec<TokenStream>, Vec<TokenStream>) {
+    let mut params = Vec::new();
+    let mut call_args = Vec::new();
+
+    for (idx, arg) in args.iter().enumerate() {
+        if arg.options.skip {
+            call_args.push(quote! { Default::default() });
+            continue;
+        }
+
+        let ident = format_ident!("__arg{}", idx);
+        let mut ty = (*arg.arg.ty).clone();
+        ReplaceRuntime {
+            params: runtime_params,
+            runtime,
+        }
+        .visit_type_mut(&mut ty);
+
+        if arg.options.flatten {
+            params.push(quote! { #ident: tauri_commands::Flatten<#ty> });
+            call_args.push(quote! { #ident.into_inner() });
+        } else {
+            params.push(quote! { #ident: #ty });
+            call_args.push(quote! { #ident });
+        }
+    }
+
+    (params, call_args)
+}
+
+/// Removes doc comments and `#[arg(...)]` from the arguments, Rust does not allow them there.
+pub(crate) fn strip_arg_attrs(sig: &mut Signature) {
+    for arg in &mut sig.inputs {
+        if let syn::FnArg::Typed(arg) = arg {
+            arg.attrs
+                .retain(|attr| !attr.path.is_ident("doc") && !attr.path.is_ident("arg"));
+        }
+    }
+}
+
+/// Replaces the generic runtime parameters of a function with the
+/// runtime the command is registered for.
+struct ReplaceRuntime<'a> {
+    params: &'a [Ident],
+    runtime: &'a Ident,
+}
+
+impl VisitMut for ReplaceRuntime<'_> {
+    fn visit_type_path_mut(&mut self, ty: &mut TypePath) {
+        if ty.qself.is_none() {
+            if let Some(ident) = ty.path.get_ident() {
+                if self.params.contains(ident) {
+                    let runtime = self.runtime;
+                    *ty = syn::parse_quote!(#runtime);
+                    return;
+                }
+            }
+        }
+
+        syn::visit_mut::visit_type_path_mut(self, ty);
+    }
+}
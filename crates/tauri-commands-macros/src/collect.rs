@@ -0,0 +1,97 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Item, ItemFn, ItemMod, LitStr};
+
+use crate::{
+    command::{configure_features, is_command_attr, register_disabled_command},
+    options::{cfg_attrs, deprecated_attr, CommandOptions},
+};
+
+pub(crate) fn expand(mut item_mod: ItemMod) -> syn::Result<TokenStream> {
+    let items = match &mut item_mod.content {
+        Some((_, items)) => items,
+        None => {
+            return Err(syn::Error::new_spanned(
+                &item_mod,
+                "commands can only be collected from inline modules",
+            ))
+        }
+    };
+
+    let mut registrations = Vec::new();
+
+    for item in items.iter() {
+        let item_fn = match item {
+            Item::Fn(item_fn) => item_fn,
+            _ => continue,
+        };
+
+        let command_attr = match item_fn.attrs.iter().find(|attr| is_command_attr(attr)) {
+            Some(attr) => attr,
+            None => continue,
+        };
+
+        registrations.push(register_function(command_attr, item_fn)?);
+    }
+
+    items.push(syn::parse_quote! {
+        /// Every `#[command]` function of this module.
+        #[allow(non_camel_case_types)]
+        pub struct all;
+    });
+
+    items.push(syn::parse_quote! {
+        impl tauri_commands::IntoCommands for all {
+            fn into_commands<R: tauri::Runtime>(
+                self,
+                __commands: &mut tauri_commands::Commands<R>,
+            ) {
+                #(#registrations)*
+            }
+        }
+    });
+
+    Ok(quote! { #item_mod })
+}
+
+/// Registers a `#[command]` function.
+///
+/// The `#[cfg]` attributes of the function are not evaluated yet, so they are
+/// applied to its registration as well, and a placeholder is registered if they do not hold.
+fn register_function(command_attr: &Attribute, item_fn: &ItemFn) -> syn::Result<TokenStream> {
+    let name = &item_fn.sig.ident;
+
+    let mut options = CommandOptions::from_attr(command_attr)?;
+    options.cfg = cfg_attrs(&item_fn.attrs)?;
+
+    if options.cfg.is_empty() {
+        return Ok(quote! { __commands.command(#name); });
+    }
+
+    if options.deprecated.is_none() {
+        options.deprecated = deprecated_attr(&item_fn.attrs)?;
+    }
+
+    let name_str = options
+        .name
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&name.to_string(), name.span()));
+
+    let cfgs = item_fn
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+    let features = configure_features(&options);
+    let disabled = register_disabled_command(&options, &name_str, &item_fn.attrs);
+
+    Ok(quote! {
+        #(#cfgs)*
+        {
+            #[allow(unused_mut)]
+            let (__name, mut __cmd) = tauri_commands::IntoCommand::into_command(#name, __commands);
+            #features
+            __commands.add_command(__name, __cmd);
+        }
+        #disabled
+    })
+}
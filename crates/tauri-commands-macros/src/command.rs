@@ -0,0 +1,422 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    visit::Visit, Attribute, FnArg, GenericParam, Ident, ItemFn, LitStr, Pat, PatType, ReturnType,
+    Signature, Type, TypeImplTrait, TypeParam, TypeParamBound, WhereClause, WherePredicate,
+};
+
+#[cfg(feature = "codegen")]
+use crate::docs::{doc_string, CommandDocs};
+#[cfg(feature = "codegen")]
+use heck::ToLowerCamelCase;
+#[cfg(feature = "codegen")]
+use syn::{Lit, LitInt, Meta, NestedMeta};
+
+use crate::{
+    args::{command_args, handler_args, is_plain, runtime_params, strip_arg_attrs, CommandArg},
+    options::{deprecated_attr, CommandOptions, RenameRule},
+};
+
+/// The maximum number of arguments supported by `InvokeArgs` and `CommandHandler` implementations.
+pub(crate) const MAX_ARGS: usize = 16;
+
+pub(crate) fn expand(mut options: CommandOptions, mut item_fn: ItemFn) -> syn::Result<TokenStream> {
+    if let Some(FnArg::Receiver(receiver)) = item_fn.sig.inputs.first() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "command functions cannot take `self`, use `#[commands]` on the impl block instead",
+        ));
+    }
+
+    check_signature(&item_fn.sig)?;
+
+    if options.deprecated.is_none() {
+        options.deprecated = deprecated_attr(&item_fn.attrs)?;
+    }
+
+    let vis = item_fn.vis.clone();
+    let name = item_fn.sig.ident.clone();
+    let name_str = options
+        .name
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&name.to_string(), name.span()));
+
+    let doc_attrs: Vec<_> = item_fn
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .cloned()
+        .collect();
+
+    let args = command_args(&item_fn.sig)?;
+    let configure = configure_command(&options, &item_fn.attrs, &args)?;
+
+    let handler = if is_plain(&args) {
+        quote! { Self::call }
+    } else {
+        let (params, call_args) =
+            handler_args(&args, &runtime_params(&item_fn.sig), &format_ident!("__R"));
+        quote! { move |#(#params),*| Self::call(#(#call_args),*) }
+    };
+
+    strip_arg_attrs(&mut item_fn.sig);
+
+    // The function stays callable from Rust as `name::call`.
+    item_fn.sig.ident = Ident::new("call", name.span());
+
+    Ok(quote! {
+        #(#doc_attrs)*
+        #[allow(non_camel_case_types)]
+        #vis struct #name;
+
+        impl #name {
+            #item_fn
+        }
+
+        #[allow(deprecated)]
+        impl tauri_commands::IntoCommand for #name {
+            fn into_command<__R: tauri::Runtime>(
+                self,
+                __commands: &mut tauri_commands::Commands<__R>,
+            ) -> (std::borrow::Cow<'static, str>, tauri_commands::Command<__R>) {
+                let mut __cmd = __commands.create_command(#handler);
+                #configure
+                (#name_str.into(), __cmd)
+            }
+        }
+    })
+}
+
+/// Features a command requires according to its cfg predicates.
+#[cfg(feature = "codegen")]
+fn required_features<'a>(
+    predicates: impl IntoIterator<Item = &'a NestedMeta>,
+    features: &mut Vec<String>,
+) {
+    for predicate in predicates {
+        match predicate {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("feature") => {
+                if let Lit::Str(s) = &nv.lit {
+                    features.push(s.value());
+                }
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("all") => {
+                required_features(&list.nested, features);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rejects signatures that cannot be turned into a command handler.
+///
+/// Receivers are not checked, they are handled by the caller.
+pub(crate) fn check_signature(sig: &Signature) -> syn::Result<()> {
+    let inputs = typed_inputs(sig);
+
+    if inputs.len() > MAX_ARGS {
+        return Err(syn::Error::new_spanned(
+            sig,
+            format!(
+                "commands can have at most {MAX_ARGS} arguments, but `{}` has {}",
+                sig.ident,
+                inputs.len()
+            ),
+        ));
+    }
+
+    let mut runtime_params = 0;
+
+    for param in &sig.generics.params {
+        match param {
+            GenericParam::Type(ty) if is_runtime_param(ty, sig.generics.where_clause.as_ref()) => {
+                runtime_params += 1;
+
+                if runtime_params > 1 {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "commands can only be generic over a single `Runtime`",
+                    ));
+                }
+            }
+            param => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "commands can only be generic over the Tauri runtime, e.g. `<R: Runtime>`",
+                ));
+            }
+        }
+    }
+
+    for arg in &inputs {
+        match &*arg.pat {
+            Pat::Ident(_) | Pat::Wild(_) => {}
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "command arguments cannot be destructured, bind them to a name instead",
+                ));
+            }
+        }
+
+        if let Some(ty) = find_impl_trait(&arg.ty) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`impl Trait` is not supported in command arguments",
+            ));
+        }
+    }
+
+    match (&sig.asyncness, &sig.output) {
+        (Some(_), ReturnType::Default) => Err(syn::Error::new_spanned(
+            sig,
+            "async commands must return a `CommandResult<T>`",
+        )),
+        (None, ReturnType::Default) => Err(syn::Error::new_spanned(
+            sig,
+            "command functions must be `async` and return a `CommandResult<T>`",
+        )),
+        (None, ReturnType::Type(_, ty)) if is_result(ty) => Err(syn::Error::new_spanned(
+            sig,
+            "command functions must be `async` or return a future",
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn is_runtime_param(ty: &TypeParam, where_clause: Option<&WhereClause>) -> bool {
+    let is_runtime_bound = |bound: &TypeParamBound| {
+        matches!(
+            bound,
+            TypeParamBound::Trait(bound)
+                if matches!(bound.path.segments.last(), Some(s) if s.ident == "Runtime")
+        )
+    };
+
+    ty.bounds.iter().any(is_runtime_bound)
+        || where_clause
+            .into_iter()
+            .flat_map(|where_clause| &where_clause.predicates)
+            .any(|predicate| {
+                matches!(
+                    predicate,
+                    WherePredicate::Type(predicate)
+                        if matches!(&predicate.bounded_ty, Type::Path(p) if p.path.is_ident(&ty.ident))
+                            && predicate.bounds.iter().any(is_runtime_bound)
+                )
+            })
+}
+
+fn is_result(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Path(p) if matches!(p.path.segments.last(), Some(s) if s.ident == "Result" || s.ident == "CommandResult")
+    )
+}
+
+fn find_impl_trait(ty: &Type) -> Option<&TypeImplTrait> {
+    struct FindImplTrait<'a>(Option<&'a TypeImplTrait>);
+
+    impl<'a> Visit<'a> for FindImplTrait<'a> {
+        fn visit_type_impl_trait(&mut self, ty: &'a TypeImplTrait) {
+            self.0.get_or_insert(ty);
+        }
+    }
+
+    let mut visitor = FindImplTrait(None);
+    visitor.visit_type(ty);
+    visitor.0
+}
+
+/// Whether the attribute is `#[command]` or `#[tauri_commands::command]`,
+/// other macros named `command` such as `#[tauri::command]` are not ours.
+pub(crate) fn is_command_attr(attr: &Attribute) -> bool {
+    let segments: Vec<_> = attr
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+
+    segments == ["command"] || segments == ["tauri_commands", "command"]
+}
+
+pub(crate) fn typed_inputs(sig: &Signature) -> Vec<&PatType> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(ty) => Some(ty),
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Statements that finish setting up `__cmd` after it was created from a handler
+/// that takes the non-skipped `args` as arguments.
+#[cfg_attr(not(feature = "codegen"), allow(unused_variables))]
+pub(crate) fn configure_command(
+    options: &CommandOptions,
+    attrs: &[Attribute],
+    args: &[CommandArg],
+) -> syn::Result<TokenStream> {
+    let rename_rule = options.rename_all.unwrap_or(RenameRule::Camel);
+
+    // Skipped arguments are not passed to the handler.
+    let args: Vec<_> = args.iter().filter(|arg| !arg.options.skip).collect();
+
+    let arg_keys: Vec<_> = args
+        .iter()
+        .enumerate()
+        .map(|(idx, arg)| match (&arg.options.rename, &*arg.arg.pat) {
+            (Some(rename), _) => rename.clone(),
+            (None, Pat::Ident(id)) => {
+                LitStr::new(&rename_rule.apply(&id.ident.to_string()), id.ident.span())
+            }
+            (None, _) => LitStr::new(&format!("_{}", idx + 1), Span::call_site()),
+        })
+        .collect();
+
+    let deprecated = options.deprecated.as_ref().map(|note| {
+        quote! { __cmd.deprecated = Some(tauri_commands::Deprecation::new(#note)); }
+    });
+
+    let deprecated_args = args.iter().zip(&arg_keys).filter_map(|(arg, key)| {
+        let note = arg.options.deprecated.as_ref()?;
+        Some(quote! { (#key.into(), tauri_commands::Deprecation::new(#note)) })
+    });
+
+    #[cfg(feature = "codegen")]
+    let codegen = {
+        let docs = CommandDocs::from_attrs(attrs);
+        let command_meta = command_meta(options, &docs);
+
+        let mut arg_meta = quote! {};
+
+        for (
+            idx,
+            CommandArg {
+                arg,
+                options: arg_options,
+            },
+        ) in args.iter().enumerate()
+        {
+            let idx_lit = LitInt::new(&idx.to_string(), Span::call_site());
+
+            let (arg_name, arg_docs) = match &*arg.pat {
+                Pat::Ident(id) => {
+                    let ident = id.ident.to_string();
+                    let name = match &arg_options.rename {
+                        Some(rename) => rename.value(),
+                        None => ident.clone(),
+                    };
+                    let arg_docs = match doc_string(&arg.attrs) {
+                        arg_docs if arg_docs.is_empty() => {
+                            docs.arg(&ident).unwrap_or_default().to_string()
+                        }
+                        arg_docs => arg_docs
+                            .lines()
+                            .map(str::trim)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    };
+
+                    (
+                        LitStr::new(&name.to_lower_camel_case(), id.ident.span()),
+                        arg_docs,
+                    )
+                }
+                _ => (
+                    LitStr::new(&format!("_{}", idx + 1), Span::call_site()),
+                    String::new(),
+                ),
+            };
+
+            let arg_docs = LitStr::new(arg_docs.trim(), Span::call_site());
+
+            arg_meta.extend(quote! {
+                __cmd.meta.args[#idx_lit].name = #arg_name.into();
+                __cmd.meta.args[#idx_lit].docs = #arg_docs.into();
+            });
+        }
+
+        quote! {
+            #command_meta
+            #arg_meta
+        }
+    };
+
+    #[cfg(not(feature = "codegen"))]
+    let codegen = quote! {};
+
+    Ok(quote! {
+        __cmd.arg_keys = vec![#(#arg_keys.into()),*];
+        #deprecated
+        __cmd.deprecated_args = vec![#(#deprecated_args),*];
+        #codegen
+    })
+}
+
+/// Registers a placeholder for the command if its cfg predicates do not hold,
+/// invoking it is an error.
+///
+/// The arguments and output of the command are unknown, as their types
+/// might only exist if the command is enabled.
+#[cfg_attr(not(feature = "codegen"), allow(unused_variables))]
+pub(crate) fn register_disabled_command(
+    options: &CommandOptions,
+    name_str: &LitStr,
+    attrs: &[Attribute],
+) -> Option<TokenStream> {
+    let predicate = options.cfg_predicate()?;
+
+    let deprecated = options.deprecated.as_ref().map(|note| {
+        quote! { __cmd.deprecated = Some(tauri_commands::Deprecation::new(#note)); }
+    });
+
+    #[cfg(feature = "codegen")]
+    let codegen = command_meta(options, &CommandDocs::from_attrs(attrs));
+
+    #[cfg(not(feature = "codegen"))]
+    let codegen = quote! {};
+
+    Some(quote! {
+        #[cfg(not(#predicate))]
+        {
+            #[allow(unused_mut)]
+            let mut __cmd = __commands.create_disabled_command();
+            #deprecated
+            #codegen
+            __commands.add_command(#name_str.into(), __cmd);
+        }
+    })
+}
+
+/// Records the features `__cmd` is gated behind.
+#[cfg(feature = "codegen")]
+pub(crate) fn configure_features(options: &CommandOptions) -> TokenStream {
+    let mut features = Vec::new();
+    required_features(&options.cfg, &mut features);
+
+    quote! { __cmd.meta.features = vec![#(#features.into()),*]; }
+}
+
+#[cfg(not(feature = "codegen"))]
+pub(crate) fn configure_features(_options: &CommandOptions) -> TokenStream {
+    quote! {}
+}
+
+/// Sets the metadata that does not depend on the arguments.
+#[cfg(feature = "codegen")]
+fn command_meta(options: &CommandOptions, docs: &CommandDocs) -> TokenStream {
+    let features = configure_features(options);
+    let doc_str = LitStr::new(&docs.description, Span::call_site());
+    let returns = LitStr::new(&docs.returns, Span::call_site());
+    let hidden = options.skip_codegen;
+
+    quote! {
+        __cmd.meta.docs = #doc_str.into();
+        __cmd.meta.returns = #returns.into();
+        __cmd.meta.hidden = #hidden;
+        #features
+    }
+}
@@ -0,0 +1,182 @@
+use syn::{Attribute, Lit, Meta};
+
+/// Documentation of a command, split into the parts that
+/// end up in different JSDoc tags.
+#[derive(Default)]
+#[cfg_attr(not(feature = "codegen"), allow(dead_code))]
+pub(crate) struct CommandDocs {
+    pub(crate) description: String,
+    /// Argument names and their descriptions from the `# Arguments` section.
+    pub(crate) args: Vec<(String, String)>,
+    /// The `# Returns` section.
+    pub(crate) returns: String,
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Description,
+    Arguments,
+    Returns,
+}
+
+#[cfg_attr(not(feature = "codegen"), allow(dead_code))]
+impl CommandDocs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Self {
+        Self::parse(&doc_string(attrs))
+    }
+
+    /// Splits off the `# Arguments` and `# Returns` sections,
+    /// everything else is kept in the description.
+    ///
+    /// Arguments are expected as a list in the usual rustdoc format:
+    ///
+    /// ```text
+    /// # Arguments
+    ///
+    /// * `name` - Description of the argument.
+    /// ```
+    pub(crate) fn parse(docs: &str) -> Self {
+        let mut this = Self::default();
+        let mut section = Section::Description;
+
+        for line in docs.lines() {
+            let trimmed = line.trim();
+
+            if trimmed.starts_with('#') {
+                section = match trimmed.trim_start_matches('#').trim() {
+                    "Arguments" | "Parameters" => Section::Arguments,
+                    "Returns" => Section::Returns,
+                    _ => Section::Description,
+                };
+
+                if section != Section::Description {
+                    continue;
+                }
+            }
+
+            match section {
+                Section::Description => {
+                    this.description += line;
+                    this.description += "\n";
+                }
+                Section::Arguments => {
+                    if let Some(item) = trimmed
+                        .strip_prefix('*')
+                        .or_else(|| trimmed.strip_prefix('-'))
+                    {
+                        let item = item.trim_start();
+                        let (name, rest) = match item.strip_prefix('`') {
+                            Some(item) => item.split_once('`').unwrap_or((item, "")),
+                            None => item
+                                .split_once(|c: char| !(c.is_alphanumeric() || c == '_'))
+                                .unwrap_or((item, "")),
+                        };
+                        let rest = rest.trim_start_matches(|c: char| {
+                            c.is_whitespace() || c == '-' || c == ':'
+                        });
+
+                        this.args.push((name.to_string(), rest.to_string()));
+                    } else if let Some((_, arg_docs)) = this.args.last_mut() {
+                        append_line(arg_docs, trimmed);
+                    }
+                }
+                Section::Returns => append_line(&mut this.returns, trimmed),
+            }
+        }
+
+        this
+    }
+
+    pub(crate) fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(arg_name, _)| arg_name == name)
+            .map(|(_, docs)| docs.as_str())
+    }
+}
+
+fn append_line(s: &mut String, line: &str) {
+    if line.is_empty() {
+        return;
+    }
+
+    if !s.is_empty() {
+        s.push(' ');
+    }
+
+    s.push_str(line);
+}
+
+/// Collects the contents of all doc comments, one line each.
+///
+/// Doc attributes that are not string literals, such as
+/// `#[doc = include_str!("...")]`, cannot be read here and are skipped.
+pub(crate) fn doc_string(attrs: &[Attribute]) -> String {
+    let mut doc_content = String::new();
+
+    for attr in attrs {
+        if attr.path.is_ident("doc") {
+            if let Ok(Meta::NameValue(nv)) = attr.parse_meta() {
+                if let Lit::Str(s) = nv.lit {
+                    doc_content += &s.value();
+                    doc_content += "\n";
+                }
+            }
+        }
+    }
+
+    doc_content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sections() {
+        let docs = CommandDocs::parse(
+            " Adds two numbers.
+
+ # Arguments
+
+ * `a` - The first
+   number.
+ * b: The second number.
+ - `c`
+
+ # Returns
+
+ The
+ sum.
+
+ # Examples
+
+ Not a section.",
+        );
+
+        assert_eq!(
+            docs.description,
+            " Adds two numbers.\n\n # Examples\n\n Not a section.\n"
+        );
+        assert_eq!(
+            docs.args,
+            [
+                ("a".to_string(), "The first number.".to_string()),
+                ("b".to_string(), "The second number.".to_string()),
+                ("c".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(docs.arg("b"), Some("The second number."));
+        assert_eq!(docs.arg("d"), None);
+        assert_eq!(docs.returns, "The sum.");
+    }
+
+    #[test]
+    fn parse_without_sections() {
+        let docs = CommandDocs::parse(" Line.\n\n Another.\n");
+
+        assert_eq!(docs.description, " Line.\n\n Another.\n");
+        assert!(docs.args.is_empty());
+        assert!(docs.returns.is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+use heck::{
+    ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
+    ToUpperCamelCase,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, AttributeArgs, Lit, LitStr, Meta, NestedMeta};
+
+/// Options given in `#[command(...)]`.
+#[derive(Default)]
+#[cfg_attr(not(feature = "codegen"), allow(dead_code))]
+pub(crate) struct CommandOptions {
+    /// The name the command is registered with instead of the function name.
+    pub(crate) name: Option<LitStr>,
+    /// The casing of argument keys in the invoke payload.
+    pub(crate) rename_all: Option<RenameRule>,
+    /// Deprecation note, empty if the command is deprecated without one.
+    pub(crate) deprecated: Option<LitStr>,
+    /// Do not generate bindings for the command.
+    pub(crate) skip_codegen: bool,
+    /// Predicates of the `#[cfg(...)]` attributes on the function, if any of them
+    /// does not hold only a placeholder of the command is registered.
+    pub(crate) cfg: Vec<NestedMeta>,
+}
+
+impl CommandOptions {
+    /// The `cfg` predicate the command is gated behind, if any.
+    pub(crate) fn cfg_predicate(&self) -> Option<TokenStream> {
+        let predicates = &self.cfg;
+
+        if predicates.is_empty() {
+            None
+        } else {
+            Some(quote! { all(#(#predicates),*) })
+        }
+    }
+
+    /// Parses the options of a `#[command]` or `#[command(...)]` attribute.
+    pub(crate) fn from_attr(attr: &Attribute) -> syn::Result<Self> {
+        match attr.parse_meta()? {
+            Meta::Path(_) => Ok(Self::default()),
+            Meta::List(list) => Self::from_args(list.nested.into_iter().collect()),
+            Meta::NameValue(nv) => Err(syn::Error::new_spanned(
+                nv,
+                "expected `#[command]` or `#[command(...)]`",
+            )),
+        }
+    }
+
+    pub(crate) fn from_args(args: AttributeArgs) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                    options.name = Some(lit_str(&nv.lit)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                    let rule = lit_str(&nv.lit)?;
+                    options.rename_all =
+                        Some(RenameRule::from_str(&rule.value()).ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                &rule,
+                                format!(
+                                    "unknown casing `{}`, expected one of {}",
+                                    rule.value(),
+                                    RenameRule::ALL.join(", ")
+                                ),
+                            )
+                        })?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("deprecated") => {
+                    options.deprecated = Some(lit_str(&nv.lit)?);
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("deprecated") => {
+                    options.deprecated = Some(LitStr::new("", p.get_ident().unwrap().span()));
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip_codegen") => {
+                    options.skip_codegen = true;
+                }
+                arg => {
+                    return Err(syn::Error::new_spanned(
+                        arg,
+                        "unknown command option, expected one of `name`, `rename_all`, `deprecated` or `skip_codegen`",
+                    ));
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Options given in `#[arg(...)]` on a command argument.
+#[derive(Default)]
+pub(crate) struct ArgOptions {
+    /// The payload key of the argument.
+    pub(crate) rename: Option<LitStr>,
+    /// Deserialize the argument from the whole payload.
+    pub(crate) flatten: bool,
+    /// Do not read the argument from the payload, use its `Default` instead.
+    pub(crate) skip: bool,
+    /// Deprecation note, empty if the argument is deprecated without one.
+    pub(crate) deprecated: Option<LitStr>,
+}
+
+impl ArgOptions {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("arg")) {
+            let args = match attr.parse_meta()? {
+                Meta::List(list) => list.nested,
+                meta => {
+                    return Err(syn::Error::new_spanned(meta, "expected `#[arg(...)]`"));
+                }
+            };
+
+            for arg in args {
+                match arg {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                        options.rename = Some(lit_str(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("flatten") => {
+                        options.flatten = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => {
+                        options.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("deprecated") => {
+                        options.deprecated = Some(lit_str(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("deprecated") => {
+                        options.deprecated = Some(LitStr::new("", p.get_ident().unwrap().span()));
+                    }
+                    arg => {
+                        return Err(syn::Error::new_spanned(
+                            arg,
+                            "unknown argument option, expected one of `rename`, `flatten`, `skip` or `deprecated`",
+                        ));
+                    }
+                }
+            }
+
+            if options.skip
+                && (options.flatten || options.rename.is_some() || options.deprecated.is_some())
+            {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "skipped arguments cannot be renamed, flattened or deprecated",
+                ));
+            }
+
+            if options.flatten && options.rename.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "flattened arguments have no key to rename",
+                ));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// The note of a `#[deprecated]` attribute, empty if it has none.
+pub(crate) fn deprecated_attr(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("deprecated")) {
+        Some(attr) => attr,
+        None => return Ok(None),
+    };
+
+    let note = match attr.parse_meta()? {
+        Meta::NameValue(nv) => lit_str(&nv.lit)?,
+        Meta::List(list) => match list.nested.iter().find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("note") => Some(&nv.lit),
+            _ => None,
+        }) {
+            Some(lit) => lit_str(lit)?,
+            None => LitStr::new("", attr.path.get_ident().unwrap().span()),
+        },
+        Meta::Path(path) => LitStr::new("", path.get_ident().unwrap().span()),
+    };
+
+    Ok(Some(note))
+}
+
+/// The predicates of all `#[cfg(...)]` attributes.
+pub(crate) fn cfg_attrs(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut predicates = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("cfg")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => predicates.extend(list.nested),
+            meta => return Err(syn::Error::new_spanned(meta, "expected `#[cfg(...)]`")),
+        }
+    }
+
+    Ok(predicates)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<LitStr> {
+    match lit {
+        Lit::Str(s) => Ok(s.clone()),
+        lit => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+/// Casing of argument keys, following the names used by serde.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const ALL: &'static [&'static str] = &[
+        "lowercase",
+        "UPPERCASE",
+        "PascalCase",
+        "camelCase",
+        "snake_case",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+        "SCREAMING-KEBAB-CASE",
+    ];
+
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn apply(self, ident: &str) -> String {
+        match self {
+            Self::Lower => ident.to_lowercase(),
+            Self::Upper => ident.to_uppercase(),
+            Self::Pascal => ident.to_upper_camel_case(),
+            Self::Camel => ident.to_lower_camel_case(),
+            Self::Snake => ident.to_snake_case(),
+            Self::ScreamingSnake => ident.to_shouty_snake_case(),
+            Self::Kebab => ident.to_kebab_case(),
+            Self::ScreamingKebab => ident.to_shouty_kebab_case(),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, FnArg, ImplItem, ImplItemMethod, ItemImpl, LitStr, Visibility};
+
+use crate::{
+    args::{command_args, handler_args, runtime_params, strip_arg_attrs},
+    command::{check_signature, configure_command, is_command_attr, register_disabled_command},
+    options::{cfg_attrs, deprecated_attr, CommandOptions},
+};
+
+pub(crate) fn expand(mut item_impl: ItemImpl) -> syn::Result<TokenStream> {
+    let mut registrations = TokenStream::new();
+
+    for item in &mut item_impl.items {
+        if let ImplItem::Method(method) = item {
+            let options = take_command_options(&mut method.attrs)?;
+
+            if is_service_method(method) && (is_public(method) || options.is_some()) {
+                let mut options = options.unwrap_or_default();
+                if options.deprecated.is_none() {
+                    options.deprecated = deprecated_attr(&method.attrs)?;
+                }
+                options.cfg = cfg_attrs(&method.attrs)?;
+
+                registrations.extend(register_method(&options, method)?);
+                strip_arg_attrs(&mut method.sig);
+            } else if options.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &method.sig,
+                    "only `async fn(&self, ...)` methods can be commands",
+                ));
+            }
+        }
+    }
+
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+
+    Ok(quote! {
+        #item_impl
+
+        impl #impl_generics tauri_commands::CommandService for #self_ty #where_clause {
+            #[allow(deprecated)]
+            fn into_commands<__R: tauri::Runtime>(
+                self: std::sync::Arc<Self>,
+                __commands: &mut tauri_commands::Commands<__R>,
+            ) {
+                #registrations
+            }
+        }
+    })
+}
+
+/// Only `async fn(&self, ...)` methods can be registered as commands.
+fn is_service_method(method: &ImplItemMethod) -> bool {
+    method.sig.asyncness.is_some()
+        && matches!(
+            method.sig.inputs.first(),
+            Some(FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_none()
+        )
+}
+
+/// Private methods are helpers unless they are annotated with `#[command]`.
+fn is_public(method: &ImplItemMethod) -> bool {
+    matches!(method.vis, Visibility::Public(_))
+}
+
+/// Removes `#[command(...)]` attributes from a method and parses their options.
+fn take_command_options(attrs: &mut Vec<Attribute>) -> syn::Result<Option<CommandOptions>> {
+    let mut options = None;
+    let mut result = Ok(());
+
+    attrs.retain(|attr| {
+        if !is_command_attr(attr) {
+            return true;
+        }
+
+        match CommandOptions::from_attr(attr) {
+            Ok(parsed) => options = Some(parsed),
+            Err(err) => result = Err(err),
+        }
+
+        false
+    });
+
+    result.map(|_| options)
+}
+
+fn register_method(options: &CommandOptions, method: &ImplItemMethod) -> syn::Result<TokenStream> {
+    check_signature(&method.sig)?;
+
+    let method_name = &method.sig.ident;
+    let name_str = options
+        .name
+        .clone()
+        .unwrap_or_else(|| LitStr::new(&method_name.to_string(), method_name.span()));
+
+    let args = command_args(&method.sig)?;
+    let (params, call_args) =
+        handler_args(&args, &runtime_params(&method.sig), &format_ident!("__R"));
+
+    let configure = configure_command(options, &method.attrs, &args)?;
+
+    let cfg = options
+        .cfg_predicate()
+        .map(|predicate| quote! { #[cfg(#predicate)] });
+
+    let disabled = register_disabled_command(options, &name_str, &method.attrs);
+
+    Ok(quote! {
+        #disabled
+        #cfg
+        {
+            let __service = std::sync::Arc::clone(&self);
+            let mut __cmd = __commands.create_command(move |#(#params),*| {
+                let __service = std::sync::Arc::clone(&__service);
+                async move { __service.#method_name(#(#call_args),*).await }
+            });
+            #configure
+            __commands.add_command(#name_str.into(), __cmd);
+        }
+    })
+}
@@ -1,133 +1,88 @@
 use std::{borrow::Cow, path::Path};
 
-use heck::ToLowerCamelCase;
-use schemars::schema::Schema;
 use tauri::Runtime;
-use yasc::{codegen::typescript::TypeScriptGenerator, collection::Collection, util::StringWriter};
 
-use crate::Commands;
+pub use tauri_commands_codegen::{
+    CheckError, FunctionNaming, Manifest, ManifestArg, ManifestCommand, NameConflict,
+    NameConflictError, NameConflicts, TypeScriptGeneratorOptions, TypeScriptOptions,
+    MANIFEST_VERSION, OPENRPC_VERSION,
+};
+
+use crate::{Commands, CommandsPlugin};
+
+mod manifest;
+
+pub use crate::API_HASH_COMMAND;
 
 #[derive(Debug, Default)]
 pub struct CommandMeta {
     pub docs: Cow<'static, str>,
     pub args: Vec<CommandArg>,
     pub output_schema: Option<schemars::schema::Schema>,
-}
-
-impl CommandMeta {
-    fn generate_ts_handler(
-        &self,
-        cmd_name: &str,
-        gen: &TypeScriptGenerator,
-        sw: &mut StringWriter,
-    ) {
-        if !self.docs.is_empty() {
-            sw.push_str("/**\n");
-
-            for line in self.docs.split('\n') {
-                sw.push_str(" * ");
-                sw.push_str(line);
-                sw.push_str("\n");
-            }
-
-            sw.push_str(" */\n");
-        }
-        sw.push_str("export function ");
-        sw.push_str(&cmd_name.to_lower_camel_case());
-        sw.push_str("(");
-
-        let mut msg_obj = StringWriter::default();
-
-        msg_obj.push_str("{");
-
-        for (idx, arg) in self.args.iter().enumerate() {
-            if arg.hidden {
-                continue;
-            }
-
-            if let Schema::Object(s) = &arg.schema {
-                sw.push_str(&arg.name);
-                sw.push_str(": ");
-                gen.generate_name_or_type(s, sw).unwrap();
-                sw.push_str(",");
-            }
-
-            let idx = idx + 1;
-            msg_obj.push_str(&format!("_{idx}: "));
-            msg_obj.push_str(&arg.name);
-            msg_obj.push_str(",");
-        }
-
-        msg_obj.push_str("}");
-
-        sw.push_str("): Promise<");
-        if let Some(s) = &self.output_schema {
-            match s {
-                Schema::Bool(s) => {
-                    if *s {
-                        sw.push_str("unknown")
-                    } else {
-                        sw.push_str("never")
-                    }
-                }
-                Schema::Object(s) => gen.generate_name_or_type(s, sw).unwrap(),
-            }
-        }
-        sw.push_str("> {");
-        sw.push_str(&format!("return invoke('{cmd_name}', {msg_obj});"));
-        sw.push_str("}\n");
-    }
+    /// Description of the output.
+    pub returns: Cow<'static, str>,
+    /// The command is handled outside of this crate, and
+    /// its arguments and output are unknown.
+    pub untyped: bool,
+    /// No bindings are generated for the command.
+    pub hidden: bool,
+    /// Cargo features the command is gated behind.
+    pub features: Vec<Cow<'static, str>>,
+    /// The command is disabled by its cfg predicates, and only a placeholder is registered.
+    pub disabled: bool,
 }
 
 #[derive(Debug)]
 pub struct CommandArg {
     pub hidden: bool,
     pub name: Cow<'static, str>,
+    pub docs: Cow<'static, str>,
+    /// The fields of the argument are spread into the payload.
+    pub flatten: bool,
     pub schema: schemars::schema::Schema,
 }
 
 impl<R: Runtime> Commands<R> {
     pub fn generate_typescript(&self) -> String {
-        let mut sw = StringWriter::default();
-
-        sw.push_str(
-            r#"import { invoke } from "@tauri-apps/api";
-"#,
-        );
-
-        let c = Collection::default();
-
-        c.add_from_generator(&self.schema_gen);
+        self.manifest().generate_typescript()
+    }
 
-        let gen = TypeScriptGenerator::new(c.clone());
+    pub fn write_typescript(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        self.manifest().write_typescript(path)
+    }
 
-        self.generate_definitions(&c, &gen, &mut sw);
+    /// Compare the bindings with the file at `path` instead of writing it.
+    pub fn check_typescript(&self, path: impl AsRef<Path>) -> Result<(), CheckError> {
+        self.manifest().check_typescript(path)
+    }
 
-        for (name, cmd) in &self.commands {
-            cmd.meta.generate_ts_handler(&*name, &gen, &mut sw);
-        }
+    /// All metadata of the commands, including hidden ones.
+    ///
+    /// The bindings, OpenRPC documents and API references are generated from it.
+    pub fn manifest(&self) -> Manifest {
+        manifest::from_commands(self.schema_gen.definitions(), &self.commands, None)
+    }
+}
 
-        sw.finish()
+impl<R: Runtime> CommandsPlugin<R> {
+    /// Generate bindings that invoke the commands through the plugin.
+    pub fn generate_typescript(&self) -> String {
+        self.manifest().generate_typescript()
     }
 
     pub fn write_typescript(&self, path: impl AsRef<Path>) -> Result<(), std::io::Error> {
-        if let Some(parent) = path.as_ref().parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        std::fs::write(path.as_ref(), self.generate_typescript())
+        self.manifest().write_typescript(path)
     }
 
-    fn generate_definitions(
-        &self,
-        c: &Collection,
-        gen: &TypeScriptGenerator,
-        sw: &mut StringWriter,
-    ) {
-        let schemas = c.read();
+    pub fn check_typescript(&self, path: impl AsRef<Path>) -> Result<(), CheckError> {
+        self.manifest().check_typescript(path)
+    }
 
-        for s in schemas.keys() {
-            gen.generate_definition(s, None, sw).unwrap();
-            sw.push_str("\n");
-        }
+    pub fn manifest(&self) -> Manifest {
+        manifest::from_commands(
+            &self.definitions,
+            &self.dispatcher.commands,
+            Some(self.name),
+        )
     }
 }
@@ -0,0 +1,71 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use schemars::{schema::Schema, Map};
+use tauri::Runtime;
+
+use super::{Manifest, ManifestArg, ManifestCommand, MANIFEST_VERSION};
+use crate::{Command, Deprecation};
+
+/// The manifest of registered commands.
+pub(crate) fn from_commands<R: Runtime>(
+    definitions: &Map<String, Schema>,
+    commands: &BTreeMap<Cow<'static, str>, Command<R>>,
+    plugin_name: Option<&str>,
+) -> Manifest {
+    Manifest {
+        version: MANIFEST_VERSION,
+        plugin: plugin_name.map(Into::into),
+        api_hash: crate::api_hash(definitions, commands),
+        definitions: definitions.clone(),
+        commands: commands
+            .iter()
+            .map(|(name, cmd)| (name.to_string(), manifest_command(cmd)))
+            .collect(),
+    }
+}
+
+fn manifest_command<R: Runtime>(cmd: &Command<R>) -> ManifestCommand {
+    let meta = &cmd.meta;
+
+    ManifestCommand {
+        docs: meta.docs.to_string(),
+        returns: meta.returns.to_string(),
+        untyped: meta.untyped,
+        hidden: meta.hidden,
+        features: meta.features.iter().map(|f| f.to_string()).collect(),
+        disabled: meta.disabled,
+        deprecated: cmd.deprecated.as_ref().map(note),
+        args: meta
+            .args
+            .iter()
+            .enumerate()
+            .map(|(idx, arg)| {
+                let key = match cmd.arg_keys.get(idx) {
+                    Some(key) => key.to_string(),
+                    None => format!("_{}", idx + 1),
+                };
+
+                let deprecated = cmd
+                    .deprecated_args
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, deprecation)| note(deprecation));
+
+                ManifestArg {
+                    name: arg.name.to_string(),
+                    key,
+                    docs: arg.docs.to_string(),
+                    hidden: arg.hidden,
+                    flatten: arg.flatten,
+                    deprecated,
+                    schema: arg.schema.clone(),
+                }
+            })
+            .collect(),
+        output: meta.output_schema.clone(),
+    }
+}
+
+fn note(deprecation: &Deprecation) -> String {
+    deprecation.note.to_string()
+}
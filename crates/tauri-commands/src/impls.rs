@@ -1,11 +1,12 @@
-use crate::{CommandHandler, FromInvoke, InvokeArgs, TauriState, TauriWindow, TauriStateManager};
+use crate::{
+    CommandHandler, Extension, Extensions, FromInvoke, InvokeArgs, TauriState, TauriStateManager,
+    TauriWindow,
+};
+use anyhow::anyhow;
+use std::borrow::Cow;
 use tauri::{Invoke, Runtime};
 
-#[cfg(not(feature = "codegen"))]
-mod simple;
-
-#[cfg(feature = "codegen")]
-mod codegen;
+mod json;
 
 macro_rules! impl_invoke_args {
     (
@@ -13,19 +14,30 @@ macro_rules! impl_invoke_args {
     ) => {
         $(
             impl<R, $($arg,)*> InvokeArgs<R> for ($($arg,)*) where R: Runtime, $($arg: FromInvoke<R>),* {
-                #[allow(unused_variables, clippy::unused_unit)]
-                fn invoke_args(invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError> {
+                #[allow(unused_variables, unused_mut, clippy::unused_unit)]
+                fn invoke_args(invoke: &Invoke<R>, arg_keys: &[Cow<'static, str>], extensions: &mut Extensions) -> Result<Self, tauri::InvokeError> {
+                    let mut arg_keys = arg_keys.iter();
                     Ok(($(
-                        $arg::from_invoke(stringify!($arg), invoke)?,
+                        $arg::from_invoke(arg_keys.next().map_or(stringify!($arg), |key| &**key), invoke, extensions)?,
                     )*))
                 }
 
+                #[allow(unused_variables)]
+                fn signature(gen: &mut schemars::gen::SchemaGenerator) -> serde_json::Value {
+                    serde_json::Value::Array(vec![$(
+                        match $arg::schema(gen) {
+                            Some(schema) => serde_json::json!({ "schema": schema, "flatten": $arg::flatten() }),
+                            None => serde_json::Value::Null,
+                        },
+                    )*])
+                }
+
                 #[cfg(feature = "codegen")]
                 #[allow(unused_variables, clippy::unused_unit)]
                 fn args(gen: &mut schemars::gen::SchemaGenerator) -> Vec<crate::codegen::CommandArg> {
                     [$(
-                        $arg::schema(gen).map(|schema| crate::codegen::CommandArg {hidden: false, name: std::borrow::Cow::Borrowed(stringify!($arg)), schema})
-                        .unwrap_or_else(|| crate::codegen::CommandArg {hidden: true, name: std::borrow::Cow::Borrowed(stringify!($arg)), schema: schemars::schema::Schema::Bool(false)}),
+                        $arg::schema(gen).map(|schema| crate::codegen::CommandArg {hidden: false, name: std::borrow::Cow::Borrowed(stringify!($arg)), docs: std::borrow::Cow::Borrowed(""), flatten: $arg::flatten(), schema})
+                        .unwrap_or_else(|| crate::codegen::CommandArg {hidden: true, name: std::borrow::Cow::Borrowed(stringify!($arg)), docs: std::borrow::Cow::Borrowed(""), flatten: false, schema: schemars::schema::Schema::Bool(false)}),
                     )*].into_iter().collect()
                 }
             }
@@ -45,6 +57,12 @@ impl_invoke_args! {
     { _1, _2, _3, _4, _5, _6, _7, _8 }
     { _1, _2, _3, _4, _5, _6, _7, _8, _9 }
     { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14, _15 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14, _15, _16 }
 }
 
 macro_rules! impl_fn_handler {
@@ -78,22 +96,59 @@ impl_fn_handler! {
     { _1, _2, _3, _4, _5, _6, _7, _8 }
     { _1, _2, _3, _4, _5, _6, _7, _8, _9 }
     { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14, _15 }
+    { _1, _2, _3, _4, _5, _6, _7, _8, _9, _10, _11, _12, _13, _14, _15, _16 }
 }
 
 impl<R: Runtime> FromInvoke<R> for TauriWindow<R> {
-    fn from_invoke(_arg_name: &str, invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError> {
+    fn from_invoke(
+        _arg_name: &str,
+        invoke: &Invoke<R>,
+        _extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
         Ok(TauriWindow(invoke.message.window()))
     }
 }
 
 impl<R: Runtime> FromInvoke<R> for TauriStateManager {
-    fn from_invoke(_arg_name: &str, invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError> {
+    fn from_invoke(
+        _arg_name: &str,
+        invoke: &Invoke<R>,
+        _extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
         Ok(TauriStateManager(invoke.message.state()))
     }
 }
 
 impl<R: Runtime, T: Send + Sync + Clone + 'static> FromInvoke<R> for TauriState<T> {
-    fn from_invoke(_arg_name: &str, invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError> {
+    fn from_invoke(
+        _arg_name: &str,
+        invoke: &Invoke<R>,
+        _extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
         Ok(TauriState((*invoke.message.state().get::<T>()).clone()))
     }
 }
+
+impl<R: Runtime, T: Send + Sync + Clone + 'static> FromInvoke<R> for Extension<T> {
+    fn from_invoke(
+        _arg_name: &str,
+        _invoke: &Invoke<R>,
+        extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
+        extensions
+            .get::<T>()
+            .cloned()
+            .map(Extension)
+            .ok_or_else(|| {
+                tauri::InvokeError::from_anyhow(anyhow!(
+                    "missing extension of type `{}`",
+                    std::any::type_name::<T>()
+                ))
+            })
+    }
+}
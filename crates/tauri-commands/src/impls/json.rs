@@ -1,4 +1,4 @@
-use crate::{FromInvoke, InvokeReply};
+use crate::{Extensions, Flatten, FromInvoke, InvokeReply};
 use serde::{de::DeserializeOwned, Serialize};
 use std::future::Future;
 use tauri::{Invoke, InvokeError, InvokeResolver, Runtime};
@@ -8,7 +8,11 @@ where
     R: Runtime,
     T: DeserializeOwned + schemars::JsonSchema,
 {
-    fn from_invoke(arg_name: &str, invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError> {
+    fn from_invoke(
+        arg_name: &str,
+        invoke: &Invoke<R>,
+        _extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
         serde_json::from_value(invoke.message.payload()[arg_name].clone())
             .map_err(|err| tauri::InvokeError::from_anyhow(err.into()))
     }
@@ -18,6 +22,30 @@ where
     }
 }
 
+impl<R, T> FromInvoke<R> for Flatten<T>
+where
+    R: Runtime,
+    T: DeserializeOwned + schemars::JsonSchema,
+{
+    fn from_invoke(
+        _arg_name: &str,
+        invoke: &Invoke<R>,
+        _extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError> {
+        serde_json::from_value(invoke.message.payload().clone())
+            .map(Flatten)
+            .map_err(|err| tauri::InvokeError::from_anyhow(err.into()))
+    }
+
+    fn schema(gen: &mut schemars::gen::SchemaGenerator) -> Option<schemars::schema::Schema> {
+        Some(gen.subschema_for::<T>())
+    }
+
+    fn flatten() -> bool {
+        true
+    }
+}
+
 impl<R: Runtime, Fut, T> InvokeReply<R> for Fut
 where
     Fut: Future<Output = Result<T, anyhow::Error>> + Send  + 'static,
@@ -1,5 +1,13 @@
-use std::{borrow::Cow, collections::HashMap, sync::Arc};
 use anyhow::anyhow;
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use tauri::{Invoke, InvokeResolver, Runtime};
 
 mod impls;
@@ -7,10 +15,13 @@ mod impls;
 #[cfg(feature = "codegen")]
 pub mod codegen;
 
-pub use tauri_commands_macros::command;
+pub use tauri_commands_macros::{collect_commands, command, commands};
 
 pub type CommandResult<T> = Result<T, anyhow::Error>;
 
+/// The built-in command that returns the API hash of the running backend.
+pub const API_HASH_COMMAND: &str = "__tauri_commands_api_hash";
+
 /// Workaround to access [`Invoke`] Tauri items, as [`FromInvoke`] cannot be implemented
 /// for them due to blanket impls and orphan rules.
 #[repr(transparent)]
@@ -60,6 +71,11 @@ impl<T> TauriState<T>
 where
     T: Send + Sync + Clone + 'static,
 {
+    /// Wrap a value, e.g. to call a command function directly.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
     pub fn into_inner(self) -> T {
         self.0
     }
@@ -76,14 +92,191 @@ where
     }
 }
 
+/// A type map of values scoped to a single invocation.
+///
+/// It is populated by [middleware](Commands::middleware) before the command
+/// arguments are extracted, and its values can be accessed with [`Extension`].
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, returning the previous value of the same type if any.
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|v| v.downcast().ok().map(|v| *v))
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_ref())
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_mut())
+    }
+
+    pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|v| v.downcast().ok().map(|v| *v))
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// Extracts a value of type `T` from the [`Extensions`] of the invocation.
+///
+/// The invocation fails if no such value was inserted.
+#[repr(transparent)]
+pub struct Extension<T>(T)
+where
+    T: Send + Sync + Clone + 'static;
+
+impl<T> Extension<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    /// Wrap a value, e.g. to call a command function directly.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Extension<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Deserializes `T` from the whole invoke payload instead of a single key,
+/// used for `#[arg(flatten)]`.
+#[repr(transparent)]
+pub struct Flatten<T>(T);
+
+impl<T> Flatten<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Flatten<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+type Middleware<R> =
+    Box<dyn Fn(&Invoke<R>, &mut Extensions) -> Result<(), tauri::InvokeError> + Send + Sync>;
+
+type Fallback<R> = Box<dyn Fn(Invoke<R>) + Send + Sync>;
+
+type Handler<R> = Box<dyn Fn(Invoke<R>, &[Cow<'static, str>], Extensions) + Send + Sync>;
+
 pub struct Command<R: Runtime> {
-    handler: Box<dyn Fn(Invoke<R>) + Send + Sync>,
+    handler: Handler<R>,
+    /// The payload keys of the arguments, positional keys (`_1`, `_2`, ...)
+    /// are used for arguments without one.
+    #[doc(hidden)]
+    pub arg_keys: Vec<Cow<'static, str>>,
+    #[doc(hidden)]
+    pub deprecated: Option<Deprecation>,
+    /// Deprecated arguments by payload key.
+    #[doc(hidden)]
+    pub deprecated_args: Vec<(Cow<'static, str>, Deprecation)>,
+    /// The schemas of the arguments and the output, part of the API hash.
+    #[doc(hidden)]
+    pub signature: serde_json::Value,
     #[cfg(feature = "codegen")]
     pub meta: codegen::CommandMeta,
 }
 
+impl<R: Runtime> Command<R> {
+    fn warn_deprecated(&self, name: &str, invoke: &Invoke<R>) {
+        if let Some(deprecation) = &self.deprecated {
+            if deprecation.first_use() {
+                tracing::warn!(
+                    command = name,
+                    note = %deprecation.note,
+                    "deprecated command invoked"
+                );
+            }
+        }
+
+        for (key, deprecation) in &self.deprecated_args {
+            if invoke.message.payload().get(&**key).is_some() && deprecation.first_use() {
+                tracing::warn!(
+                    command = name,
+                    argument = %key,
+                    note = %deprecation.note,
+                    "deprecated command argument used"
+                );
+            }
+        }
+    }
+}
+
+/// Deprecation of a command or one of its arguments.
+#[doc(hidden)]
+pub struct Deprecation {
+    /// The deprecation note, empty if there is none.
+    pub note: Cow<'static, str>,
+    warned: AtomicBool,
+}
+
+impl Deprecation {
+    pub fn new(note: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            note: note.into(),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    /// Only the first use of a deprecated item is logged.
+    fn first_use(&self) -> bool {
+        !self.warned.swap(true, Ordering::Relaxed)
+    }
+}
+
 pub trait InvokeArgs<R: Runtime>: Sized {
-    fn invoke_args(invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError>;
+    fn invoke_args(
+        invoke: &Invoke<R>,
+        arg_keys: &[Cow<'static, str>],
+        extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError>;
+
+    /// The schemas of the payload arguments, `null` for the ones that are not
+    /// part of the payload.
+    #[doc(hidden)]
+    fn signature(_gen: &mut schemars::gen::SchemaGenerator) -> serde_json::Value {
+        serde_json::Value::Null
+    }
 
     #[cfg(feature = "codegen")]
     #[doc(hidden)]
@@ -93,18 +286,25 @@ pub trait InvokeArgs<R: Runtime>: Sized {
 }
 
 trait FromInvoke<R: Runtime>: Sized {
-    fn from_invoke(arg_name: &str, invoke: &Invoke<R>) -> Result<Self, tauri::InvokeError>;
+    fn from_invoke(
+        arg_name: &str,
+        invoke: &Invoke<R>,
+        extensions: &mut Extensions,
+    ) -> Result<Self, tauri::InvokeError>;
 
-    #[cfg(feature = "codegen")]
     fn schema(_gen: &mut schemars::gen::SchemaGenerator) -> Option<schemars::schema::Schema> {
         None
     }
+
+    /// The argument is spread into the payload instead of being a single key.
+    fn flatten() -> bool {
+        false
+    }
 }
 
 pub trait InvokeReply<R: Runtime> {
     fn reply(self, resolver: InvokeResolver<R>);
 
-    #[cfg(feature = "codegen")]
     fn schema(_gen: &mut schemars::gen::SchemaGenerator) -> Option<schemars::schema::Schema> {
         None
     }
@@ -116,46 +316,152 @@ pub trait CommandHandler<Args> {
 }
 
 pub struct Commands<R: Runtime> {
-    #[cfg(feature = "codegen")]
     #[doc(hidden)]
     pub schema_gen: schemars::gen::SchemaGenerator,
-    commands: HashMap<Cow<'static, str>, Command<R>>,
+    commands: BTreeMap<Cow<'static, str>, Command<R>>,
+    middleware: Vec<Middleware<R>>,
+    fallback: Option<Fallback<R>>,
 }
 
 impl<R: Runtime> Commands<R> {
     pub fn new() -> Self {
         Self {
-            #[cfg(feature = "codegen")]
             schema_gen: schemars::gen::SchemaSettings::default().into_generator(),
             commands: Default::default(),
+            middleware: Vec::new(),
+            fallback: None,
         }
     }
 
     pub fn into_invoke_handler(self) -> impl Fn(Invoke<R>) + Send + Sync + 'static {
-        move |invoke: Invoke<R>| {
-            let cmd_name = invoke.message.command();
+        let dispatcher = self.into_dispatcher();
+        move |invoke: Invoke<R>| dispatcher.invoke(invoke)
+    }
 
-            match self.commands.get(cmd_name) {
-                Some(c) => (c.handler)(invoke),
-                None => invoke
-                    .resolver
-                    .invoke_error(tauri::InvokeError::from_anyhow(anyhow!(
-                        "no handler found for {cmd_name}"
-                    ))),
-            }
+    /// Package the commands as a Tauri plugin with the given name.
+    ///
+    /// The commands of the plugin are invoked as `plugin:{name}|{command}`.
+    pub fn into_plugin(self, name: &'static str) -> CommandsPlugin<R> {
+        CommandsPlugin {
+            name,
+            #[cfg(feature = "codegen")]
+            definitions: self.schema_gen.definitions().clone(),
+            dispatcher: self.into_dispatcher(),
+        }
+    }
+
+    fn into_dispatcher(self) -> Dispatcher<R> {
+        Dispatcher {
+            api_hash: self.api_hash(),
+            commands: self.commands,
+            middleware: self.middleware,
+            fallback: self.fallback,
         }
     }
 
+    /// A stable hash of the command names, their argument keys and the schemas of
+    /// their arguments and outputs, the generated bindings can compare it with
+    /// the running backend to detect that they are stale.
+    ///
+    /// The hash is the same with and without the `codegen` feature.
+    pub fn api_hash(&self) -> String {
+        api_hash(self.schema_gen.definitions(), &self.commands)
+    }
+
+    /// Add a middleware that runs before every command in the order of registration.
+    ///
+    /// Middleware can insert values into the [`Extensions`] of the invocation
+    /// that commands can then access with [`Extension`], or reject the invocation
+    /// by returning an error.
+    pub fn middleware<F>(&mut self, middleware: F) -> &mut Self
+    where
+        F: Fn(&Invoke<R>, &mut Extensions) -> Result<(), tauri::InvokeError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Set a handler for invocations that do not match any registered command.
+    ///
+    /// The handler receives the unmatched [`Invoke`] as-is, so it can be used
+    /// to delegate to a handler created with [`tauri::generate_handler`].
+    /// Middleware does not run for the fallback handler.
+    pub fn fallback<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(Invoke<R>) + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Register a command.
     pub fn command(&mut self, command: impl IntoCommand) -> &mut Self {
         let (name, cmd) = command.into_command(self);
-        if self.commands.contains_key(&name) {
-            panic!("command handler for command `{name}` already exists");
+        self.add_command(name, cmd)
+    }
+
+    /// Register every command of a module annotated with [`macro@collect_commands`],
+    /// e.g. `commands.collect(api::all)`.
+    ///
+    /// Commands disabled with `#[cfg(...)]` are registered as placeholders that fail
+    /// when invoked.
+    pub fn collect(&mut self, commands: impl IntoCommands) -> &mut Self {
+        commands.into_commands(self);
+        self
+    }
+
+    /// Register every command of a service defined with [`macro@commands`].
+    ///
+    /// Methods disabled with `#[cfg(...)]` are registered as placeholders that fail
+    /// when invoked.
+    pub fn service<S: CommandService>(&mut self, service: Arc<S>) -> &mut Self {
+        service.into_commands(self);
+        self
+    }
+
+    /// Register commands that are handled by an existing Tauri invoke handler,
+    /// such as one created with [`tauri::generate_handler`].
+    ///
+    /// The commands are dispatched like any other command, and
+    /// untyped bindings are generated for them. Use [`Commands::fallback`] instead
+    /// to delegate without listing the command names.
+    pub fn tauri_handler<F>(&mut self, command_names: &[&str], handler: F) -> &mut Self
+    where
+        F: Fn(Invoke<R>) + Send + Sync + 'static,
+    {
+        let handler = Arc::new(handler);
+
+        for &command_name in command_names {
+            let handler = handler.clone();
+
+            let cmd = Command {
+                handler: Box::new(move |invoke, _, _| handler(invoke)),
+                arg_keys: Vec::new(),
+                deprecated: None,
+                deprecated_args: Vec::new(),
+                signature: serde_json::Value::Null,
+                #[cfg(feature = "codegen")]
+                meta: codegen::CommandMeta {
+                    untyped: true,
+                    ..Default::default()
+                },
+            };
+
+            self.add_command(Cow::Owned(command_name.to_string()), cmd);
         }
-        self.commands.insert(name, cmd);
+
         self
     }
 
-    pub fn handler<Args, F>(&mut self, command_name: &str, description: &str, handler: F) -> &mut Self
+    pub fn handler<Args, F>(
+        &mut self,
+        command_name: &str,
+        description: &str,
+        handler: F,
+    ) -> &mut Self
     where
         Args: InvokeArgs<R>,
         F: CommandHandler<Args> + Send + Sync + 'static,
@@ -168,14 +474,15 @@ impl<R: Runtime> Commands<R> {
             cmd.meta.docs = Cow::Owned(description.to_string());
         }
 
-        if self
-            .commands
-            .insert(Cow::Owned(command_name.to_string()), cmd)
-            .is_some()
-        {
-            panic!("command handler for command `{command_name}` already exists");
-        }
+        self.add_command(Cow::Owned(command_name.to_string()), cmd)
+    }
 
+    #[doc(hidden)]
+    pub fn add_command(&mut self, name: Cow<'static, str>, cmd: Command<R>) -> &mut Self {
+        if self.commands.contains_key(&name) {
+            panic!("command handler for command `{name}` already exists");
+        }
+        self.commands.insert(name, cmd);
         self
     }
 
@@ -186,24 +493,37 @@ impl<R: Runtime> Commands<R> {
         F: CommandHandler<Args> + Send + Sync + 'static,
         F::Output: InvokeReply<R>,
     {
-
-        let handler = Box::new(move |invoke| match Args::invoke_args(&invoke) {
-            Ok(args) => {
-                handler.handle(args).reply(invoke.resolver);
-            }
-            Err(err) => {
-                invoke.resolver.invoke_error(err);
-            }
+        let handler = Box::new(
+            move |invoke: Invoke<R>, arg_keys: &[Cow<'static, str>], mut extensions: Extensions| {
+                match Args::invoke_args(&invoke, arg_keys, &mut extensions) {
+                    Ok(args) => {
+                        handler.handle(args).reply(invoke.resolver);
+                    }
+                    Err(err) => {
+                        invoke.resolver.invoke_error(err);
+                    }
+                }
+            },
+        );
+
+        let signature = serde_json::json!({
+            "args": Args::signature(&mut self.schema_gen),
+            "output": F::Output::schema(&mut self.schema_gen),
         });
 
         #[cfg(feature = "codegen")]
         {
             Command {
                 handler,
+                arg_keys: Vec::new(),
+                deprecated: None,
+                deprecated_args: Vec::new(),
+                signature,
                 meta: codegen::CommandMeta {
                     docs: "".into(),
                     args: Args::args(&mut self.schema_gen),
                     output_schema: F::Output::schema(&mut self.schema_gen),
+                    ..Default::default()
                 },
             }
         }
@@ -212,9 +532,39 @@ impl<R: Runtime> Commands<R> {
         {
             Command {
                 handler,
+                arg_keys: Vec::new(),
+                deprecated: None,
+                deprecated_args: Vec::new(),
+                signature,
             }
         }
     }
+
+    /// A placeholder for a command that is disabled by its cfg predicates.
+    #[doc(hidden)]
+    pub fn create_disabled_command(&self) -> Command<R> {
+        Command {
+            handler: Box::new(|invoke, _, _| {
+                let err = anyhow!(
+                    "command {} is not enabled in this build",
+                    invoke.message.command()
+                );
+                invoke
+                    .resolver
+                    .invoke_error(tauri::InvokeError::from_anyhow(err));
+            }),
+            arg_keys: Vec::new(),
+            deprecated: None,
+            deprecated_args: Vec::new(),
+            signature: serde_json::Value::Null,
+            #[cfg(feature = "codegen")]
+            meta: codegen::CommandMeta {
+                untyped: true,
+                disabled: true,
+                ..Default::default()
+            },
+        }
+    }
 }
 
 impl<R: Runtime> Default for Commands<R> {
@@ -223,6 +573,110 @@ impl<R: Runtime> Default for Commands<R> {
     }
 }
 
+/// The part of [`Commands`] that is needed to handle invocations.
+struct Dispatcher<R: Runtime> {
+    api_hash: String,
+    commands: BTreeMap<Cow<'static, str>, Command<R>>,
+    middleware: Vec<Middleware<R>>,
+    fallback: Option<Fallback<R>>,
+}
+
+impl<R: Runtime> Dispatcher<R> {
+    fn invoke(&self, invoke: Invoke<R>) {
+        let cmd_name = invoke.message.command();
+
+        if cmd_name == API_HASH_COMMAND {
+            invoke.resolver.respond(Ok(self.api_hash.clone()));
+            return;
+        }
+
+        match self.commands.get(cmd_name) {
+            Some(c) => {
+                c.warn_deprecated(cmd_name, &invoke);
+
+                let mut extensions = Extensions::new();
+
+                for middleware in &self.middleware {
+                    if let Err(err) = middleware(&invoke, &mut extensions) {
+                        invoke.resolver.invoke_error(err);
+                        return;
+                    }
+                }
+
+                (c.handler)(invoke, &c.arg_keys, extensions)
+            }
+            None => match &self.fallback {
+                Some(fallback) => fallback(invoke),
+                None => invoke
+                    .resolver
+                    .invoke_error(tauri::InvokeError::from_anyhow(anyhow!(
+                        "no handler found for {cmd_name}"
+                    ))),
+            },
+        }
+    }
+}
+
+/// Hashes the commands and the schema definitions they refer to, in a stable order.
+fn api_hash<R: Runtime>(
+    definitions: &schemars::Map<String, schemars::schema::Schema>,
+    commands: &BTreeMap<Cow<'static, str>, Command<R>>,
+) -> String {
+    let commands: Vec<_> = commands
+        .iter()
+        .map(|(name, cmd)| {
+            serde_json::json!({
+                "name": name,
+                "args": cmd.arg_keys,
+                "signature": cmd.signature,
+            })
+        })
+        .collect();
+
+    let json = serde_json::json!({
+        "commands": commands,
+        "definitions": definitions,
+    })
+    .to_string();
+
+    format!("{:016x}", fnv1a(json.as_bytes()))
+}
+
+/// 64-bit FNV-1a, unlike `DefaultHasher` it is the same across Rust versions.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A Tauri plugin created with [`Commands::into_plugin`].
+pub struct CommandsPlugin<R: Runtime> {
+    name: &'static str,
+    #[cfg(feature = "codegen")]
+    definitions: schemars::Map<String, schemars::schema::Schema>,
+    dispatcher: Dispatcher<R>,
+}
+
+impl<R: Runtime> CommandsPlugin<R> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn api_hash(&self) -> String {
+        self.dispatcher.api_hash.clone()
+    }
+}
+
+impl<R: Runtime> tauri::plugin::Plugin<R> for CommandsPlugin<R> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn extend_api(&mut self, invoke: Invoke<R>) {
+        self.dispatcher.invoke(invoke);
+    }
+}
+
 #[doc(hidden)]
 pub trait IntoCommand {
     fn into_command<R: Runtime>(
@@ -230,3 +684,13 @@ pub trait IntoCommand {
         commands: &mut Commands<R>,
     ) -> (Cow<'static, str>, Command<R>);
 }
+
+#[doc(hidden)]
+pub trait IntoCommands {
+    fn into_commands<R: Runtime>(self, commands: &mut Commands<R>);
+}
+
+#[doc(hidden)]
+pub trait CommandService: Send + Sync + 'static {
+    fn into_commands<R: Runtime>(self: Arc<Self>, commands: &mut Commands<R>);
+}
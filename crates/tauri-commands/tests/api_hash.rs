@@ -0,0 +1,32 @@
+use tauri_commands::{CommandResult, Commands};
+
+fn api_hash<F>(register: F) -> String
+where
+    F: FnOnce(&mut Commands<tauri::Wry>),
+{
+    let mut commands = Commands::new();
+    register(&mut commands);
+    commands.api_hash()
+}
+
+#[test]
+fn api_hash_includes_types() {
+    let string_arg = api_hash(|c| {
+        c.handler("hello", "", |name: String| async move {
+            CommandResult::Ok(name)
+        });
+    });
+    let number_arg = api_hash(|c| {
+        c.handler("hello", "", |name: u32| async move {
+            CommandResult::Ok(name.to_string())
+        });
+    });
+    let number_output = api_hash(|c| {
+        c.handler("hello", "", |name: String| async move {
+            CommandResult::Ok(name.len())
+        });
+    });
+
+    assert_ne!(string_arg, number_arg);
+    assert_ne!(string_arg, number_output);
+}
@@ -0,0 +1,58 @@
+use tauri_commands::{collect_commands, Commands};
+
+#[collect_commands]
+mod api {
+    use tauri_commands::{command, CommandResult};
+
+    #[command]
+    pub async fn enabled() -> CommandResult<()> {
+        Ok(())
+    }
+
+    #[cfg(any())]
+    #[command]
+    pub async fn disabled() -> CommandResult<()> {
+        Ok(())
+    }
+
+    #[cfg(feature = "codegen")]
+    #[command]
+    pub async fn gated() -> CommandResult<()> {
+        Ok(())
+    }
+}
+
+fn commands() -> Commands<tauri::Wry> {
+    let mut commands = Commands::new();
+    commands.collect(api::all);
+    commands
+}
+
+#[test]
+fn disabled_commands_are_placeholders() {
+    let mut expected = Commands::<tauri::Wry>::new();
+    expected.command(api::enabled);
+    #[cfg(feature = "codegen")]
+    expected.command(api::gated);
+    #[cfg(not(feature = "codegen"))]
+    {
+        let gated = expected.create_disabled_command();
+        expected.add_command("gated".into(), gated);
+    }
+    let disabled = expected.create_disabled_command();
+    expected.add_command("disabled".into(), disabled);
+
+    assert_eq!(commands().api_hash(), expected.api_hash());
+
+    #[cfg(feature = "codegen")]
+    {
+        let manifest = commands().manifest();
+
+        assert!(!manifest.commands["enabled"].disabled);
+        assert!(manifest.commands["disabled"].disabled);
+        assert!(manifest.commands["disabled"].untyped);
+
+        assert!(!manifest.commands["gated"].disabled);
+        assert_eq!(manifest.commands["gated"].features, ["codegen"]);
+    }
+}
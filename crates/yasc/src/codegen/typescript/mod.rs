@@ -4,7 +4,7 @@
 
 use crate::{
     collection::Collection,
-    util::{docs_of, type_name_of, SchemaObjectExt, StringWriter},
+    util::{docs_of, type_name_of, CodeWriter, SchemaObjectExt},
 };
 use anyhow::{anyhow, Context};
 use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
@@ -49,7 +49,7 @@ impl TypeScriptGenerator {
         &self,
         id: &Url,
         type_name: Option<String>,
-        out: &mut StringWriter,
+        out: &mut CodeWriter,
     ) -> Result<(), anyhow::Error> {
         let schemas = self.collection.read();
 
@@ -64,24 +64,16 @@ impl TypeScriptGenerator {
         let opts = self.options.clone();
 
         if let Some(docs) = docs_of(schema).map(str::trim) {
-            if !docs.is_empty() {
-                out.push_str("/**\n");
-
-                for line in docs.split('\n') {
-                    out.push_str(" * ");
-                    out.push_str(line);
-                    out.push_str("\n");
-                }
-
-                out.push_str(" */\n");
-            }
+            out.doc_comment(docs);
         }
 
         if opts.export_definitions {
             out.push_str("export ");
         }
 
-        if schema.is_single_object() && opts.use_interface {
+        let interface = schema.is_single_object() && opts.use_interface;
+
+        if interface {
             out.push_str("interface ");
             out.push_str(&type_name);
             out.push_str(" ");
@@ -99,13 +91,17 @@ impl TypeScriptGenerator {
                 .with_context(|| format!(r#"Failed to generate type for schema {}"#, id))?;
         }
 
+        if !interface {
+            out.push_str(";");
+        }
+
         Ok(())
     }
 
     pub fn generate_type(
         &self,
         schema: &SchemaObject,
-        out: &mut StringWriter,
+        out: &mut CodeWriter,
     ) -> Result<(), anyhow::Error> {
         if let Some(c) = &schema.const_value {
             serde_json::to_writer_pretty(&mut *out, c)?;
@@ -128,7 +124,7 @@ impl TypeScriptGenerator {
         let mut subschemas_written = false;
 
         if let Some(subschemas) = &schema.subschemas {
-            let mut optional_out = StringWriter::new();
+            let mut optional_out = out.detached();
 
             if let Some(oneof_schemas) = &subschemas.one_of {
                 for oneof_schema in oneof_schemas {
@@ -181,7 +177,7 @@ impl TypeScriptGenerator {
                 out.push_str(")");
             }
 
-            let mut required_out = StringWriter::new();
+            let mut required_out = out.detached();
 
             if let Some(allof_schemas) = &subschemas.all_of {
                 if !optional_out.is_empty() || optional_out.bytes_written() > 0 {
@@ -255,7 +251,7 @@ impl TypeScriptGenerator {
         &self,
         schema: &SchemaObject,
         ty: InstanceType,
-        out: &mut StringWriter,
+        out: &mut CodeWriter,
     ) -> Result<(), anyhow::Error> {
         match ty {
             InstanceType::Null => out.push_str("null"),
@@ -263,11 +259,11 @@ impl TypeScriptGenerator {
             InstanceType::Number | InstanceType::Integer => out.push_str("number"),
             InstanceType::String => out.push_str("string"),
             InstanceType::Object => {
-                out.push_str("{\n");
+                let mut body = out.detached();
 
                 match &schema.object {
                     Some(obj) => {
-                        let mut additional_types = StringWriter::new();
+                        let mut additional_types = out.detached();
 
                         if let Some(additional_props) = &obj.additional_properties {
                             match &**additional_props {
@@ -305,9 +301,9 @@ impl TypeScriptGenerator {
                         }
 
                         if !additional_types.is_empty() {
-                            out.push_str("[key: string]: ");
-                            out.push_str(&additional_types.finish());
-                            out.push_str(";\n");
+                            body.push_str("[key: string]: ");
+                            body.push_str(&additional_types.finish());
+                            body.push_str(";\n");
                         }
 
                         for (prop_name, prop_schema) in &obj.properties {
@@ -315,48 +311,47 @@ impl TypeScriptGenerator {
 
                             if let Schema::Object(o) = &prop_schema {
                                 if let Some(docs) = docs_of(o).map(str::trim) {
-                                    if !docs.is_empty() {
-                                        out.push_str("/**\n");
-
-                                        for line in docs.split('\n') {
-                                            out.push_str(" * ");
-                                            out.push_str(line);
-                                            out.push_str("\n");
-                                        }
-
-                                        out.push_str(" */\n");
-                                    }
+                                    body.doc_comment(docs);
                                 }
                             }
 
-                            out.push_str(prop_name);
+                            body.push_str(prop_name);
                             if !required {
-                                out.push_str("?");
+                                body.push_str("?");
                             }
 
-                            out.push_str(": ");
+                            body.push_str(": ");
 
                             match prop_schema {
                                 Schema::Bool(b) => {
                                     if *b {
-                                        out.push_str("unknown;");
+                                        body.push_str("unknown");
                                     } else {
-                                        out.push_str("never;");
+                                        body.push_str("never");
                                     }
                                 }
                                 Schema::Object(prop_object) => {
-                                    self.generate_name_or_type(prop_object, out)?;
-                                    out.push_str(";\n");
+                                    self.generate_name_or_type(prop_object, &mut body)?;
                                 }
                             }
+
+                            body.push_str(";\n");
                         }
                     }
                     None => {
-                        out.push_str("[key: string]: unknown;");
+                        body.push_str("[key: string]: unknown;\n");
                     }
                 }
 
-                out.push_str("}");
+                if body.is_empty() {
+                    out.push_str("{}");
+                } else {
+                    out.push_str("{\n");
+                    out.indent();
+                    out.push_str(&body.finish());
+                    out.dedent();
+                    out.push_str("}");
+                }
             }
             InstanceType::Array => match &schema.array {
                 Some(arr) => match &arr.items {
@@ -378,7 +373,11 @@ impl TypeScriptGenerator {
                         SingleOrVec::Vec(item_schemas) => {
                             out.push_str("[");
 
-                            for item_schema in item_schemas {
+                            for (idx, item_schema) in item_schemas.iter().enumerate() {
+                                if idx > 0 {
+                                    out.push_str(", ");
+                                }
+
                                 match item_schema {
                                     Schema::Bool(b) => {
                                         if *b {
@@ -391,7 +390,6 @@ impl TypeScriptGenerator {
                                         self.generate_name_or_type(array_items_schema, out)?;
                                     }
                                 }
-                                out.push_str(",");
                             }
 
                             out.push_str("]");
@@ -409,7 +407,7 @@ impl TypeScriptGenerator {
     pub fn generate_name_or_type(
         &self,
         schema: &SchemaObject,
-        out: &mut StringWriter,
+        out: &mut CodeWriter,
     ) -> Result<(), anyhow::Error> {
         if let Some(Ok(r)) = schema
             .reference
@@ -2,7 +2,10 @@ use crate::{HashMap, HashSet};
 use anyhow::Context as ErrorContext;
 use async_recursion::async_recursion;
 use parking_lot::{RwLock, RwLockReadGuard};
-use schemars::{schema::SchemaObject, visit::Visitor};
+use schemars::{
+    schema::{Schema, SchemaObject},
+    visit::Visitor,
+};
 use std::sync::Arc;
 use url::Url;
 
@@ -23,13 +26,24 @@ impl Collection {
     pub fn read(&self) -> RwLockReadGuard<'_, HashMap<Url, SchemaObject>> {
         self.schemas.read_recursive()
     }
+
+    /// The ids of all schemas in a stable order, unlike iterating [`Collection::read`].
+    pub fn sorted_ids(&self) -> Vec<Url> {
+        let mut ids: Vec<_> = self.schemas.read_recursive().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
 }
 
 impl Collection {
     pub fn add_from_generator(&self, generator: &schemars::gen::SchemaGenerator) {
+        self.add_definitions(generator.definitions());
+    }
+
+    pub fn add_definitions(&self, definitions: &schemars::Map<String, Schema>) {
         let mut schemas = self.schemas.write();
 
-        for (name, schema) in generator.definitions() {
+        for (name, schema) in definitions {
             let mut cr = CollectReferences::default();
 
             let mut schema = schema.clone().into_object();
@@ -89,6 +89,144 @@ impl StringWriter {
     }
 }
 
+/// A writer for source code that indents lines by the current level.
+///
+/// Pushed text is indented after every newline, so code written into a
+/// [`CodeWriter::detached`] writer keeps its relative indentation when pushed back.
+#[derive(Debug, Clone)]
+pub struct CodeWriter {
+    buf: String,
+    written: usize,
+    indent: String,
+    level: usize,
+    line_start: bool,
+}
+
+impl Default for CodeWriter {
+    fn default() -> Self {
+        Self::with_indent("  ")
+    }
+}
+
+impl io::Write for CodeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_str(
+            std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        );
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for CodeWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.buf.fmt(f)
+    }
+}
+
+impl CodeWriter {
+    /// A writer indenting with two spaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_indent(indent: impl Into<String>) -> Self {
+        Self {
+            buf: String::new(),
+            written: 0,
+            indent: indent.into(),
+            level: 0,
+            line_start: true,
+        }
+    }
+
+    /// An empty writer with the same indentation unit.
+    #[must_use]
+    pub fn detached(&self) -> Self {
+        Self::with_indent(self.indent.clone())
+    }
+
+    /// Writes the string, empty lines are not indented.
+    pub fn push_str(&mut self, string: &str) {
+        self.written += string.len();
+
+        for (idx, line) in string.split('\n').enumerate() {
+            if idx > 0 {
+                self.buf.push('\n');
+                self.line_start = true;
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if self.line_start {
+                for _ in 0..self.level {
+                    self.buf.push_str(&self.indent);
+                }
+                self.line_start = false;
+            }
+
+            self.buf.push_str(line);
+        }
+    }
+
+    /// Writes the string followed by a newline.
+    pub fn line(&mut self, string: &str) {
+        self.push_str(string);
+        self.push_str("\n");
+    }
+
+    pub fn indent(&mut self) {
+        self.level += 1;
+    }
+
+    pub fn dedent(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    /// Writes a `/** */` comment, nothing is written if the docs are empty.
+    pub fn doc_comment(&mut self, docs: &str) {
+        let docs = docs.trim_end();
+
+        if docs.is_empty() {
+            return;
+        }
+
+        self.line("/**");
+
+        for line in docs.lines() {
+            if line.is_empty() {
+                self.line(" *");
+            } else {
+                self.line(&format!(" * {line}"));
+            }
+        }
+
+        self.line(" */");
+    }
+
+    pub fn finish(self) -> String {
+        self.buf
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn take(&mut self) -> String {
+        self.line_start = true;
+        mem::take(&mut self.buf)
+    }
+
+    pub fn bytes_written(&self) -> usize {
+        self.written
+    }
+}
+
 pub fn docs_of(schema: &SchemaObject) -> Option<&str> {
     schema
         .metadata
@@ -0,0 +1,19 @@
+//! Generates the TypeScript bindings without launching the app.
+//!
+//! `cargo run --bin bindings` writes them, `cargo run --bin bindings -- --check`
+//! fails if they are out of date instead, e.g. in CI.
+
+use hello_tauri_vite::BINDINGS_PATH;
+
+fn main() {
+    let commands = hello_tauri_vite::commands::<tauri::Wry>();
+
+    if std::env::args().any(|arg| arg == "--check") {
+        if let Err(err) = commands.check_typescript(BINDINGS_PATH) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    } else {
+        commands.write_typescript(BINDINGS_PATH).unwrap();
+    }
+}
@@ -0,0 +1,50 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::Runtime;
+use tauri_commands::{command, CommandResult, Commands, TauriWindow};
+
+/// The request data.
+#[derive(Deserialize, JsonSchema)]
+struct HelloRequest {
+    /// This message is printed to stdout.
+    message: String,
+}
+
+/// A reply for hello.
+#[derive(Serialize, JsonSchema)]
+struct HelloReply {
+    /// The message to be written to the console.
+    message: String,
+}
+
+/// Send a friendly message and receive a reply.
+#[command]
+async fn hello(request: HelloRequest) -> CommandResult<HelloReply> {
+    println!("{}", request.message);
+    Ok(HelloReply {
+        message: "hello from tauri!".into(),
+    })
+}
+
+/// Commands defined as functions have to be generic over the runtime.
+#[command]
+async fn show_window<R: Runtime>(window: TauriWindow<R>) -> CommandResult<()> {
+    window.show().unwrap();
+    Ok(())
+}
+
+/// All commands of the app, also used to generate the bindings without running it.
+pub fn commands<R: Runtime>() -> Commands<R> {
+    let mut commands = Commands::new();
+
+    commands.command(hello).command(show_window).handler(
+        "add numbers",
+        "adds numbers",
+        |a: i32, b: i32| async move { Ok(a + b) },
+    );
+
+    commands
+}
+
+/// Where the generated bindings are written to.
+pub const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/tauri.ts");
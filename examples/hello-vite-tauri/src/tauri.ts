@@ -1,33 +1,56 @@
 import { invoke } from "@tauri-apps/api";
+
+export const API_HASH = "669e02e029114f59";
+
 /**
  * A reply for hello.
  */
 export interface HelloReply {
-/**
- * The message to be written to the console.
- */
-message: string;
+  /**
+   * The message to be written to the console.
+   */
+  message: string;
 }
+
 /**
  * The request data.
  */
 export interface HelloRequest {
+  /**
+   * This message is printed to stdout.
+   */
+  message: string;
+}
+
 /**
- * This message is printed to stdout.
+ * adds numbers
  */
-message: string;
+export function addNumbers(_1: number, _2: number): Promise<number> {
+  return invoke('add numbers', { _1, _2 });
 }
+
 /**
- *  Send a friendly message and receive a reply.
- * 
+ * Send a friendly message and receive a reply.
  */
-export function hello(request: HelloRequest,): Promise<HelloReply> {return invoke('hello', {_1: request,});}
+export function hello(request: HelloRequest): Promise<HelloReply> {
+  return invoke('hello', { request });
+}
+
 /**
- * adds numbers
+ * Commands defined as functions have to be generic over the runtime.
  */
-export function addNumbers(_1: number,_2: number,): Promise<number> {return invoke('add numbers', {_1: _1,_2: _2,});}
+export function showWindow(): Promise<null> {
+  return invoke('show_window', {});
+}
+
 /**
- *  Commands defined as functions have to be generic over the runtime.
- * 
+ * Whether the running backend has the same commands as these bindings,
+ * also `false` if asking the backend fails.
  */
-export function showWindow(): Promise<null> {return invoke('show_window', {});}
+export async function checkApiHash(): Promise<boolean> {
+  try {
+    return (await invoke('__tauri_commands_api_hash')) === API_HASH;
+  } catch {
+    return false;
+  }
+}